@@ -0,0 +1,12 @@
+CREATE TABLE `github_webhook_events` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`delivery_id` text,
+	`event` text NOT NULL,
+	`payload` text NOT NULL,
+	`attempts` integer DEFAULT 0 NOT NULL,
+	`next_attempt` integer,
+	`last_error` text,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE INDEX `github_webhook_events_next_attempt_idx` ON `github_webhook_events` (`next_attempt`) WHERE "github_webhook_events"."next_attempt" is not null;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE `github_webhook_events` ADD `processed` integer;--> statement-breakpoint
+CREATE UNIQUE INDEX `github_webhook_events_delivery_id_idx` ON `github_webhook_events` (`delivery_id`);--> statement-breakpoint
+CREATE INDEX `github_webhook_events_created_idx` ON `github_webhook_events` (`created`);
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE `github_routes` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`repository` text NOT NULL,
+	`event` text,
+	`branch` text,
+	`channel_id` integer NOT NULL,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+ALTER TABLE `github_messages` ADD `channel_id` integer;
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE `github_push_filters` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`repository` text NOT NULL,
+	`allow_branches` text NOT NULL,
+	`deny_branches` text NOT NULL,
+	`ignore_bots` integer DEFAULT false NOT NULL,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `github_push_filters_repository_idx` ON `github_push_filters` (`repository`);
\ No newline at end of file
@@ -0,0 +1,24 @@
+CREATE TABLE `github_pull_requests` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`repository_id` integer NOT NULL,
+	`number` integer NOT NULL,
+	`channel_id` integer NOT NULL,
+	`message_id` integer,
+	`state` text NOT NULL,
+	`title` text NOT NULL,
+	`url` text NOT NULL,
+	`author` text NOT NULL,
+	`base_branch` text NOT NULL,
+	`head_sha` text NOT NULL,
+	`labels` text NOT NULL,
+	`requested_reviewers` text NOT NULL,
+	`reviews` text NOT NULL,
+	`additions` integer DEFAULT 0 NOT NULL,
+	`deletions` integer DEFAULT 0 NOT NULL,
+	`changed_files` integer DEFAULT 0 NOT NULL,
+	`workflow_status` text NOT NULL,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `github_pull_requests_repository_id_number_idx` ON `github_pull_requests` (`repository_id`,`number`);--> statement-breakpoint
+CREATE INDEX `github_pull_requests_repository_id_head_sha_idx` ON `github_pull_requests` (`repository_id`,`head_sha`);
\ No newline at end of file
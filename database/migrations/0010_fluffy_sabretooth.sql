@@ -0,0 +1 @@
+ALTER TABLE `github_pull_requests` ADD `thread_id` integer;
\ No newline at end of file
@@ -0,0 +1,12 @@
+CREATE TABLE `github_issues` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`repository_id` integer NOT NULL,
+	`number` integer NOT NULL,
+	`channel_id` integer NOT NULL,
+	`message_id` integer NOT NULL,
+	`thread_id` integer,
+	`closed` integer DEFAULT false NOT NULL,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `github_issues_repository_id_number_idx` ON `github_issues` (`repository_id`,`number`);
\ No newline at end of file
@@ -0,0 +1,4 @@
+ALTER TABLE `github_webhook_events` ADD `deferred_repository_id` integer;--> statement-breakpoint
+ALTER TABLE `github_webhook_events` ADD `deferred_sha` text;--> statement-breakpoint
+CREATE INDEX `github_webhook_events_deferred_idx` ON `github_webhook_events` (`deferred_repository_id`,`deferred_sha`) WHERE "github_webhook_events"."deferred_sha" is not null;--> statement-breakpoint
+ALTER TABLE `github_messages` ADD `title` text;
\ No newline at end of file
@@ -0,0 +1,4 @@
+ALTER TABLE `github_messages` ADD `forced` integer DEFAULT false NOT NULL;--> statement-breakpoint
+ALTER TABLE `github_messages` ADD `before_sha` text;--> statement-breakpoint
+ALTER TABLE `github_messages` ADD `updated` integer;--> statement-breakpoint
+CREATE INDEX `github_messages_branch_idx` ON `github_messages` (`repository_id`,`branch`);
\ No newline at end of file
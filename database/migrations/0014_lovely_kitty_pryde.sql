@@ -0,0 +1,19 @@
+CREATE TABLE `github_security_alerts` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`kind` text NOT NULL,
+	`repository` text NOT NULL,
+	`key` text NOT NULL,
+	`channel_id` integer NOT NULL,
+	`message_id` integer,
+	`state` text NOT NULL,
+	`severity` text,
+	`summary` text NOT NULL,
+	`package` text,
+	`vulnerable_range` text,
+	`url` text NOT NULL,
+	`acknowledged_by` integer,
+	`acknowledged` integer,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `github_security_alerts_key_idx` ON `github_security_alerts` (`kind`,`repository`,`key`);
\ No newline at end of file
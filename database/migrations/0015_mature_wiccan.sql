@@ -0,0 +1,10 @@
+CREATE TABLE `github_discussions` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`repository_id` integer NOT NULL,
+	`number` integer NOT NULL,
+	`channel_id` integer NOT NULL,
+	`thread_id` integer NOT NULL,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `github_discussions_repository_id_number_idx` ON `github_discussions` (`repository_id`,`number`);
\ No newline at end of file
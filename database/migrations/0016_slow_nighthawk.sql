@@ -0,0 +1,13 @@
+CREATE TABLE `github_repository_stars` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`repository_id` integer NOT NULL,
+	`repository` text NOT NULL,
+	`stars` integer NOT NULL,
+	`last_milestone` integer DEFAULT 0 NOT NULL,
+	`starred` integer DEFAULT 0 NOT NULL,
+	`unstarred` integer DEFAULT 0 NOT NULL,
+	`digest_started` integer DEFAULT (strftime('%s','now')) NOT NULL,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `github_repository_stars_repository_id_idx` ON `github_repository_stars` (`repository_id`);
\ No newline at end of file
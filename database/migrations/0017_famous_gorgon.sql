@@ -0,0 +1 @@
+ALTER TABLE `github_webhook_events` ADD `source` text;
\ No newline at end of file
@@ -0,0 +1,9 @@
+CREATE TABLE `sponsors_activities` (
+	`id` text PRIMARY KEY NOT NULL,
+	`action` text,
+	`timestamp` integer,
+	`data` text NOT NULL,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE INDEX `sponsors_activities_timestamp_idx` ON `sponsors_activities` (`timestamp`);
\ No newline at end of file
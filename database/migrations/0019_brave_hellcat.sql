@@ -0,0 +1,12 @@
+CREATE TABLE `sponsor_links` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`discord_id` integer NOT NULL,
+	`github_id` integer,
+	`github_login` text NOT NULL,
+	`code` text NOT NULL,
+	`verified` integer,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `sponsor_links_discord_id_idx` ON `sponsor_links` (`discord_id`);--> statement-breakpoint
+CREATE UNIQUE INDEX `sponsor_links_github_id_idx` ON `sponsor_links` (`github_id`) WHERE "sponsor_links"."github_id" is not null;
\ No newline at end of file
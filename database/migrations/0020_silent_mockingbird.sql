@@ -0,0 +1,9 @@
+CREATE TABLE `sponsor_roles` (
+	`id` integer PRIMARY KEY NOT NULL,
+	`role_id` integer NOT NULL,
+	`kind` text NOT NULL,
+	`amount_in_cents` integer DEFAULT 0 NOT NULL,
+	`created` integer DEFAULT (strftime('%s','now')) NOT NULL
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `sponsor_roles_role_id_kind_idx` ON `sponsor_roles` (`role_id`,`kind`);
\ No newline at end of file
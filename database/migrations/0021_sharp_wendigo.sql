@@ -0,0 +1 @@
+ALTER TABLE `github_repository_stars` ADD `source` text;
\ No newline at end of file
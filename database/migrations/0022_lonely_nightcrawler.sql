@@ -0,0 +1,2 @@
+DELETE FROM `github_messages` WHERE `id` NOT IN (SELECT MAX(`id`) FROM `github_messages` GROUP BY `repository_id`, `workflow_sha`);--> statement-breakpoint
+CREATE UNIQUE INDEX `github_messages_workflow_sha_idx` ON `github_messages` (`repository_id`,`workflow_sha`);
\ No newline at end of file
@@ -4,18 +4,25 @@ import { index, integer, text, sqliteTable, uniqueIndex } from "drizzle-orm/sqli
 export const githubMessages = sqliteTable('github_messages', {
 	id: integer('id').primaryKey().notNull(),
 	repositoryId: integer('repository_id').notNull(),
+	channelId: integer('channel_id'),
 	messageId: integer('message_id').notNull(),
 
 	commits: text('commits', { mode: 'json' }).notNull(),
 
 	branch: text('branch').default('').notNull(),
+	title: text('title'),
+	forced: integer('forced', { mode: 'boolean' }).default(false).notNull(),
 
+	beforeSha: text('before_sha'),
 	workflowSha: text('workflow_sha').notNull(),
 	workflowStatus: text('workflow_status', { mode: 'json' }).notNull(),
 
 	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+	updated: integer('updated', { mode: 'timestamp' }),
 }, (githubMessages) => [
-	index('github_messages_repository_id_idx').on(githubMessages.repositoryId)
+	index('github_messages_repository_id_idx').on(githubMessages.repositoryId),
+	index('github_messages_branch_idx').on(githubMessages.repositoryId, githubMessages.branch),
+	uniqueIndex('github_messages_workflow_sha_idx').on(githubMessages.repositoryId, githubMessages.workflowSha)
 ])
 
 export const textMessages = sqliteTable('text_messages', {
@@ -49,3 +56,186 @@ export const sentSponsorships = sqliteTable('sent_sponsorships', {
 	index('sent_sponsorships_created_idx').on(sentSponsorships.created),
 	uniqueIndex('sent_sponsorships_message_id_idx').on(sentSponsorships.messageId).where(isNotNull(sentSponsorships.messageId))
 ])
+
+export const sponsorsActivities = sqliteTable('sponsors_activities', {
+	id: text('id').primaryKey().notNull(),
+	action: text('action'),
+	timestamp: integer('timestamp', { mode: 'timestamp' }),
+	data: text('data').notNull(),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (sponsorsActivities) => [
+	index('sponsors_activities_timestamp_idx').on(sponsorsActivities.timestamp)
+])
+
+export const sponsorLinks = sqliteTable('sponsor_links', {
+	id: integer('id').primaryKey().notNull(),
+	discordId: integer('discord_id').notNull(),
+	githubId: integer('github_id'),
+	githubLogin: text('github_login').notNull(),
+	code: text('code').notNull(),
+	verified: integer('verified', { mode: 'timestamp' }),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (sponsorLinks) => [
+	uniqueIndex('sponsor_links_discord_id_idx').on(sponsorLinks.discordId),
+	uniqueIndex('sponsor_links_github_id_idx').on(sponsorLinks.githubId).where(isNotNull(sponsorLinks.githubId))
+])
+
+export const sponsorRoles = sqliteTable('sponsor_roles', {
+	id: integer('id').primaryKey().notNull(),
+	roleId: integer('role_id').notNull(),
+	kind: text('kind').notNull(),
+	amountInCents: integer('amount_in_cents').default(0).notNull(),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (sponsorRoles) => [
+	uniqueIndex('sponsor_roles_role_id_kind_idx').on(sponsorRoles.roleId, sponsorRoles.kind)
+])
+
+export const githubWebhookEvents = sqliteTable('github_webhook_events', {
+	id: integer('id').primaryKey().notNull(),
+	deliveryId: text('delivery_id'),
+	source: text('source'),
+
+	event: text('event').notNull(),
+	payload: text('payload').notNull(),
+
+	attempts: integer('attempts').default(0).notNull(),
+	nextAttempt: integer('next_attempt', { mode: 'timestamp' }),
+	lastError: text('last_error'),
+	processed: integer('processed', { mode: 'timestamp' }),
+	deferredRepositoryId: integer('deferred_repository_id'),
+	deferredSha: text('deferred_sha'),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (githubWebhookEvents) => [
+	uniqueIndex('github_webhook_events_delivery_id_idx').on(githubWebhookEvents.deliveryId),
+	index('github_webhook_events_next_attempt_idx').on(githubWebhookEvents.nextAttempt).where(isNotNull(githubWebhookEvents.nextAttempt)),
+	index('github_webhook_events_created_idx').on(githubWebhookEvents.created),
+	index('github_webhook_events_deferred_idx').on(githubWebhookEvents.deferredRepositoryId, githubWebhookEvents.deferredSha).where(isNotNull(githubWebhookEvents.deferredSha))
+])
+
+export const githubRoutes = sqliteTable('github_routes', {
+	id: integer('id').primaryKey().notNull(),
+
+	repository: text('repository').notNull(),
+	event: text('event'),
+	branch: text('branch'),
+
+	channelId: integer('channel_id').notNull(),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+})
+
+export const githubPushFilters = sqliteTable('github_push_filters', {
+	id: integer('id').primaryKey().notNull(),
+	repository: text('repository').notNull(),
+
+	allowBranches: text('allow_branches', { mode: 'json' }).notNull(),
+	denyBranches: text('deny_branches', { mode: 'json' }).notNull(),
+	ignoreBots: integer('ignore_bots', { mode: 'boolean' }).default(false).notNull(),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (githubPushFilters) => [
+	uniqueIndex('github_push_filters_repository_idx').on(githubPushFilters.repository)
+])
+
+export const githubPullRequests = sqliteTable('github_pull_requests', {
+	id: integer('id').primaryKey().notNull(),
+	repositoryId: integer('repository_id').notNull(),
+	number: integer('number').notNull(),
+	channelId: integer('channel_id').notNull(),
+	messageId: integer('message_id'),
+	threadId: integer('thread_id'),
+
+	state: text('state').notNull(),
+	title: text('title').notNull(),
+	url: text('url').notNull(),
+	author: text('author').notNull(),
+	baseBranch: text('base_branch').notNull(),
+	headSha: text('head_sha').notNull(),
+
+	labels: text('labels', { mode: 'json' }).notNull(),
+	requestedReviewers: text('requested_reviewers', { mode: 'json' }).notNull(),
+	reviews: text('reviews', { mode: 'json' }).notNull(),
+
+	additions: integer('additions').default(0).notNull(),
+	deletions: integer('deletions').default(0).notNull(),
+	changedFiles: integer('changed_files').default(0).notNull(),
+
+	workflowStatus: text('workflow_status', { mode: 'json' }).notNull(),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (githubPullRequests) => [
+	uniqueIndex('github_pull_requests_repository_id_number_idx').on(githubPullRequests.repositoryId, githubPullRequests.number),
+	index('github_pull_requests_repository_id_head_sha_idx').on(githubPullRequests.repositoryId, githubPullRequests.headSha)
+])
+
+export const githubIssues = sqliteTable('github_issues', {
+	id: integer('id').primaryKey().notNull(),
+	repositoryId: integer('repository_id').notNull(),
+	number: integer('number').notNull(),
+	channelId: integer('channel_id').notNull(),
+	messageId: integer('message_id').notNull(),
+	threadId: integer('thread_id'),
+
+	closed: integer('closed', { mode: 'boolean' }).default(false).notNull(),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (githubIssues) => [
+	uniqueIndex('github_issues_repository_id_number_idx').on(githubIssues.repositoryId, githubIssues.number)
+])
+
+export const githubSecurityAlerts = sqliteTable('github_security_alerts', {
+	id: integer('id').primaryKey().notNull(),
+	kind: text('kind').notNull(),
+	repository: text('repository').notNull(),
+	key: text('key').notNull(),
+	channelId: integer('channel_id').notNull(),
+	messageId: integer('message_id'),
+
+	state: text('state').notNull(),
+	severity: text('severity'),
+	summary: text('summary').notNull(),
+	package: text('package'),
+	vulnerableRange: text('vulnerable_range'),
+	url: text('url').notNull(),
+
+	acknowledgedBy: integer('acknowledged_by'),
+	acknowledged: integer('acknowledged', { mode: 'timestamp' }),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (githubSecurityAlerts) => [
+	uniqueIndex('github_security_alerts_key_idx').on(githubSecurityAlerts.kind, githubSecurityAlerts.repository, githubSecurityAlerts.key)
+])
+
+export const githubDiscussions = sqliteTable('github_discussions', {
+	id: integer('id').primaryKey().notNull(),
+	repositoryId: integer('repository_id').notNull(),
+	number: integer('number').notNull(),
+	channelId: integer('channel_id').notNull(),
+	threadId: integer('thread_id').notNull(),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (githubDiscussions) => [
+	uniqueIndex('github_discussions_repository_id_number_idx').on(githubDiscussions.repositoryId, githubDiscussions.number)
+])
+
+export const githubRepositoryStars = sqliteTable('github_repository_stars', {
+	id: integer('id').primaryKey().notNull(),
+	repositoryId: integer('repository_id').notNull(),
+	repository: text('repository').notNull(),
+	source: text('source'),
+
+	stars: integer('stars').notNull(),
+	lastMilestone: integer('last_milestone').default(0).notNull(),
+
+	starred: integer('starred').default(0).notNull(),
+	unstarred: integer('unstarred').default(0).notNull(),
+	digestStarted: integer('digest_started', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+
+	created: integer('created', { mode: 'timestamp' }).default(sql`(strftime('%s','now'))`).notNull(),
+}, (githubRepositoryStars) => [
+	uniqueIndex('github_repository_stars_repository_id_idx').on(githubRepositoryStars.repositoryId)
+])
@@ -0,0 +1,237 @@
+use poise::CreateReply;
+use serenity::all::GuildChannel;
+
+/// Manage GitHub webhook notifications.
+#[poise::command(
+    slash_command,
+    rename = "github",
+    subcommands(
+        "admin_github_route_add_command",
+        "admin_github_route_remove_command",
+        "admin_github_route_list_command",
+        "admin_github_push_filter_set_command",
+        "admin_github_push_filter_remove_command",
+        "admin_github_push_filter_list_command",
+        "admin_github_replay_command"
+    )
+)]
+pub async fn admin_github_command(_ctx: crate::BotContext<'_>) -> Result<(), anyhow::Error> {
+    Ok(())
+}
+
+/// Route matching GitHub events to a channel.
+#[poise::command(slash_command, rename = "route-add")]
+pub async fn admin_github_route_add_command(
+    ctx: crate::BotContext<'_>,
+    #[description = "The channel to send matching events to"] channel: GuildChannel,
+    #[description = "Repository name glob, e.g. calagopus/* (organization login for org events)"]
+    repository: String,
+    #[description = "Only route this event kind, e.g. push"]
+    #[autocomplete = "crate::models::autocomplete_github_event_name"]
+    event: Option<String>,
+    #[description = "Only route events on branches matching this glob, e.g. release/*"]
+    branch: Option<String>,
+) -> Result<(), anyhow::Error> {
+    let route: crate::models::GithubRoute = sqlx::query_as(
+        "INSERT INTO github_routes (repository, event, branch, channel_id) VALUES (?, ?, ?, ?) RETURNING *",
+    )
+    .bind(repository.trim())
+    .bind(event.as_deref().map(str::trim))
+    .bind(branch.as_deref().map(str::trim))
+    .bind(channel.id.get() as i64)
+    .fetch_one(ctx.data().database.write())
+    .await?;
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!("GitHub route added. ({})", route.describe()))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Remove an existing GitHub route.
+#[poise::command(slash_command, rename = "route-remove")]
+pub async fn admin_github_route_remove_command(
+    ctx: crate::BotContext<'_>,
+    #[description = "The route to remove"]
+    #[autocomplete = "crate::models::autocomplete_github_route_id"]
+    route: u64,
+) -> Result<(), anyhow::Error> {
+    let Some(route): Option<crate::models::GithubRoute> =
+        sqlx::query_as("DELETE FROM github_routes WHERE id = ? RETURNING *")
+            .bind(route as i64)
+            .fetch_optional(ctx.data().database.write())
+            .await?
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("GitHub route not found.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!("GitHub route removed. ({})", route.describe()))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// List all GitHub routes.
+#[poise::command(slash_command, rename = "route-list")]
+pub async fn admin_github_route_list_command(
+    ctx: crate::BotContext<'_>,
+) -> Result<(), anyhow::Error> {
+    let routes = crate::models::all_github_routes(ctx.data().database.read()).await?;
+
+    let mut content = String::new();
+    for route in &routes {
+        content.push_str(&format!("`#{}` {}\n", route.id, route.describe()));
+    }
+
+    content.push_str(&format!(
+        "-# Unmatched events go to <#{}>.",
+        ctx.data().env.github_channel_id
+    ));
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+fn split_patterns(patterns: Option<&str>) -> Vec<String> {
+    patterns
+        .unwrap_or_default()
+        .split(',')
+        .map(|pattern| pattern.trim())
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| pattern.to_string())
+        .collect()
+}
+
+/// Configure which pushes to a repository are announced.
+#[poise::command(slash_command, rename = "push-filter-set")]
+pub async fn admin_github_push_filter_set_command(
+    ctx: crate::BotContext<'_>,
+    #[description = "Repository name glob, e.g. calagopus/*"] repository: String,
+    #[description = "Comma separated branch globs to announce, all if empty"] allow: Option<String>,
+    #[description = "Comma separated branch globs to never announce, e.g. dependabot/*,gh-pages"]
+    deny: Option<String>,
+    #[description = "Whether to skip pushes made by bots"] ignore_bots: Option<bool>,
+) -> Result<(), anyhow::Error> {
+    let filter: crate::models::GithubPushFilter = sqlx::query_as(
+        "INSERT INTO github_push_filters (repository, allow_branches, deny_branches, ignore_bots) VALUES (?, ?, ?, ?)
+         ON CONFLICT (repository) DO UPDATE SET allow_branches = excluded.allow_branches, deny_branches = excluded.deny_branches, ignore_bots = excluded.ignore_bots
+         RETURNING *",
+    )
+    .bind(repository.trim())
+    .bind(serde_json::to_string(&split_patterns(allow.as_deref()))?)
+    .bind(serde_json::to_string(&split_patterns(deny.as_deref()))?)
+    .bind(ignore_bots.unwrap_or(false))
+    .fetch_one(ctx.data().database.write())
+    .await?;
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!("Push filter saved. ({})", filter.describe()))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Remove an existing push filter.
+#[poise::command(slash_command, rename = "push-filter-remove")]
+pub async fn admin_github_push_filter_remove_command(
+    ctx: crate::BotContext<'_>,
+    #[description = "The push filter to remove"]
+    #[autocomplete = "crate::models::autocomplete_github_push_filter_id"]
+    filter: u64,
+) -> Result<(), anyhow::Error> {
+    let Some(filter): Option<crate::models::GithubPushFilter> =
+        sqlx::query_as("DELETE FROM github_push_filters WHERE id = ? RETURNING *")
+            .bind(filter as i64)
+            .fetch_optional(ctx.data().database.write())
+            .await?
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("Push filter not found.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!("Push filter removed. ({})", filter.describe()))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// List all push filters.
+#[poise::command(slash_command, rename = "push-filter-list")]
+pub async fn admin_github_push_filter_list_command(
+    ctx: crate::BotContext<'_>,
+) -> Result<(), anyhow::Error> {
+    let filters = crate::models::all_github_push_filters(ctx.data().database.read()).await?;
+
+    let content = if filters.is_empty() {
+        "No push filters configured, all pushes are announced.".to_string()
+    } else {
+        filters
+            .iter()
+            .map(|filter| format!("`#{}` {}", filter.id, filter.describe()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Render a stored GitHub delivery again.
+#[poise::command(slash_command, rename = "replay")]
+pub async fn admin_github_replay_command(
+    ctx: crate::BotContext<'_>,
+    #[description = "The delivery to replay"]
+    #[autocomplete = "crate::models::autocomplete_github_webhook_delivery"]
+    delivery: String,
+    #[description = "Dry run, send fresh messages here without editing or saving anything"]
+    channel: Option<GuildChannel>,
+) -> Result<(), anyhow::Error> {
+    ctx.defer_ephemeral().await?;
+
+    let content = match crate::routes::github::replay_github_event(
+        &ctx.data(),
+        delivery.trim(),
+        channel.map(|channel| channel.id.get()),
+    )
+    .await
+    {
+        Ok(Some(replayed)) => format!("GitHub {} delivery replayed.", replayed.event),
+        Ok(None) => "GitHub delivery not found, it may have been pruned.".to_string(),
+        Err(err) => format!("Failed to replay GitHub delivery: `{err:#}`"),
+    };
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
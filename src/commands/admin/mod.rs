@@ -1,10 +1,16 @@
+mod github;
+mod sponsors;
 mod text_message;
 
 /// Manage administrative commands.
 #[poise::command(
     slash_command,
     rename = "admin",
-    subcommands("text_message::admin_text_message_command"),
+    subcommands(
+        "github::admin_github_command",
+        "sponsors::admin_sponsors_command",
+        "text_message::admin_text_message_command"
+    ),
     default_member_permissions = "ADMINISTRATOR"
 )]
 pub async fn admin_command(_ctx: crate::BotContext<'_>) -> Result<(), anyhow::Error> {
@@ -0,0 +1,194 @@
+use crate::models::{SponsorRole, SponsorRoleRequirement};
+use poise::CreateReply;
+use serenity::all::Role;
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum SponsorRoleKind {
+    #[name = "monthly"]
+    Monthly,
+    #[name = "lifetime"]
+    Lifetime,
+    #[name = "former"]
+    Former,
+}
+
+impl SponsorRoleKind {
+    #[inline]
+    fn kind(self) -> &'static str {
+        match self {
+            Self::Monthly => "monthly",
+            Self::Lifetime => "lifetime",
+            Self::Former => "former",
+        }
+    }
+}
+
+/// Manage GitHub sponsor roles.
+#[poise::command(
+    slash_command,
+    rename = "sponsors",
+    subcommands(
+        "admin_sponsors_role_add_command",
+        "admin_sponsors_role_remove_command",
+        "admin_sponsors_role_list_command"
+    )
+)]
+pub async fn admin_sponsors_command(_ctx: crate::BotContext<'_>) -> Result<(), anyhow::Error> {
+    Ok(())
+}
+
+/// How many sponsors qualify for each role, and how many of those linked their account
+async fn preview(
+    state: &crate::routes::State,
+    roles: &[SponsorRole],
+) -> Result<Vec<String>, anyhow::Error> {
+    let ledger = crate::sponsors::cached_ledger(&state.env, &state.database).await?;
+    let now = chrono::Utc::now();
+    let evaluation = crate::sponsors::evaluate(&ledger, now);
+    let links = crate::models::verified_sponsor_links(state.database.read()).await?;
+
+    Ok(roles
+        .iter()
+        .map(|role| {
+            let qualifies = |sponsor: &crate::sponsors::EvaluatedSponsor| {
+                role.qualifies(sponsor, now, state.env.github_sponsors_role_grace)
+            };
+
+            let sponsors = evaluation
+                .sponsors
+                .iter()
+                .filter(|sponsor| qualifies(sponsor))
+                .count();
+            let linked = links
+                .iter()
+                .filter(|link| {
+                    crate::sponsors::linked_sponsor(&evaluation, link).is_some_and(qualifies)
+                })
+                .count();
+
+            format!(
+                "`#{}` {}, {sponsors} sponsor(s) qualify, {linked} linked",
+                role.id,
+                role.describe()
+            )
+        })
+        .collect())
+}
+
+/// Lists roles with their preview, or without it if the sponsors could not be fetched
+async fn describe_roles(state: &crate::routes::State, roles: &[SponsorRole]) -> String {
+    match preview(state, roles).await {
+        Ok(lines) => lines.join("\n"),
+        Err(err) => {
+            tracing::warn!("failed to preview sponsor roles: {:?}", err);
+
+            roles
+                .iter()
+                .map(|role| format!("`#{}` {}", role.id, role.describe()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+/// Grant a role to linked sponsors meeting a requirement.
+#[poise::command(slash_command, rename = "role-add")]
+pub async fn admin_sponsors_role_add_command(
+    ctx: crate::BotContext<'_>,
+    #[description = "The role to grant"] role: Role,
+    #[description = "What sponsors need to qualify"] kind: SponsorRoleKind,
+    #[description = "Minimum amount in dollars, required for monthly and lifetime"]
+    #[min = 0]
+    amount: Option<f64>,
+) -> Result<(), anyhow::Error> {
+    let cents = amount.map(|amount| (amount * 100.0).round() as i64);
+
+    let requirement = match (kind, cents) {
+        (SponsorRoleKind::Monthly, Some(cents)) => SponsorRoleRequirement::Monthly(cents),
+        (SponsorRoleKind::Lifetime, Some(cents)) => SponsorRoleRequirement::Lifetime(cents),
+        (SponsorRoleKind::Former, _) => SponsorRoleRequirement::Former,
+        _ => {
+            ctx.send(
+                CreateReply::default()
+                    .content("An amount is required for monthly and lifetime roles.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    ctx.defer_ephemeral().await?;
+
+    let role =
+        crate::models::upsert_sponsor_role(ctx.data().database.write(), role.id.get(), requirement)
+            .await?;
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Sponsor role saved. {}",
+                describe_roles(&ctx.data(), std::slice::from_ref(&role)).await
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Stop granting a role to sponsors, members keep it until removed by hand.
+#[poise::command(slash_command, rename = "role-remove")]
+pub async fn admin_sponsors_role_remove_command(
+    ctx: crate::BotContext<'_>,
+    #[description = "The role to stop granting"] role: Role,
+    #[description = "Only remove this requirement, all of them if empty"] kind: Option<
+        SponsorRoleKind,
+    >,
+) -> Result<(), anyhow::Error> {
+    let roles = crate::models::delete_sponsor_roles(
+        ctx.data().database.write(),
+        role.id.get(),
+        kind.map(SponsorRoleKind::kind),
+    )
+    .await?;
+
+    let content = if roles.is_empty() {
+        "Sponsor role not found.".to_string()
+    } else {
+        format!(
+            "Sponsor role removed.\n{}",
+            roles
+                .iter()
+                .map(|role| format!("`#{}` {}", role.id, role.describe()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    };
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// List all sponsor roles and how many sponsors qualify for them.
+#[poise::command(slash_command, rename = "role-list")]
+pub async fn admin_sponsors_role_list_command(
+    ctx: crate::BotContext<'_>,
+) -> Result<(), anyhow::Error> {
+    let roles = crate::models::all_sponsor_roles(ctx.data().database.read()).await?;
+
+    let content = if roles.is_empty() {
+        "No sponsor roles configured.".to_string()
+    } else {
+        ctx.defer_ephemeral().await?;
+
+        describe_roles(&ctx.data(), &roles).await
+    };
+
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
 mod admin;
+mod security;
+mod sponsor;
+mod sponsors;
 mod status;
 
 pub struct CommandList {
@@ -29,4 +32,7 @@ impl CommandList {
 pub fn commands(list: CommandList) -> CommandList {
     list.add_command(status::status_command())
         .add_command(admin::admin_command())
+        .add_command(security::security_command())
+        .add_command(sponsor::sponsor_command())
+        .add_command(sponsors::sponsors_command())
 }
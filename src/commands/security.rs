@@ -0,0 +1,80 @@
+use poise::CreateReply;
+
+/// Handle GitHub security alerts.
+#[poise::command(
+    slash_command,
+    rename = "security",
+    subcommands("security_acknowledge_command"),
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn security_command(_ctx: crate::BotContext<'_>) -> Result<(), anyhow::Error> {
+    Ok(())
+}
+
+/// Take responsibility for a security alert.
+#[poise::command(slash_command, rename = "acknowledge")]
+pub async fn security_acknowledge_command(
+    ctx: crate::BotContext<'_>,
+    #[description = "The alert to acknowledge"]
+    #[autocomplete = "crate::models::autocomplete_github_security_alert_id"]
+    alert: u64,
+) -> Result<(), anyhow::Error> {
+    // alerts are only visible in the restricted channel, so only act from there
+    if ctx.data().env.github_security_channel_id != Some(ctx.channel_id().get()) {
+        ctx.send(
+            CreateReply::default()
+                .content("This command can only be used in the security channel.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let Some(mut alert): Option<crate::models::GithubSecurityAlert> =
+        sqlx::query_as("SELECT * FROM github_security_alerts WHERE id = ?")
+            .bind(alert as i64)
+            .fetch_optional(ctx.data().database.read())
+            .await?
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("Security alert not found.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    if let Some(acknowledged_by) = alert.acknowledged_by {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "Security alert already acknowledged by <@{acknowledged_by}>. ({})",
+                    alert.describe()
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    alert.acknowledged_by = Some(ctx.author().id.get() as i64);
+    alert.acknowledged = Some(chrono::Utc::now());
+
+    crate::models::update_github_security_alert(ctx.data().database.write(), &alert).await?;
+    alert
+        .send_or_update(&ctx.serenity_context().http, &ctx.data().database)
+        .await?;
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Security alert acknowledged. ({})",
+                alert.describe()
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
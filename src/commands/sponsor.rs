@@ -0,0 +1,213 @@
+use poise::CreateReply;
+use sha2::Digest;
+
+/// Link your GitHub sponsorship to your Discord account.
+#[poise::command(
+    slash_command,
+    rename = "sponsor",
+    subcommands("sponsor_link_command", "sponsor_unlink_command")
+)]
+pub async fn sponsor_command(_ctx: crate::BotContext<'_>) -> Result<(), anyhow::Error> {
+    Ok(())
+}
+
+/// Recomputes the roles of a single member right away instead of waiting for the next sync,
+/// without a link all of their sponsor roles are removed
+async fn sync_member_roles(
+    state: &crate::routes::State,
+    discord_id: u64,
+    link: Option<&crate::models::SponsorLink>,
+) -> Result<(), anyhow::Error> {
+    let Some(guild_id) = state.env.github_sponsors_guild_id else {
+        return Ok(());
+    };
+
+    let roles = crate::models::all_sponsor_roles(state.database.read()).await?;
+    if roles.is_empty() {
+        return Ok(());
+    }
+
+    let Some(link) = link else {
+        return crate::sponsors::apply_sponsor_roles(state, &roles, guild_id, discord_id, None)
+            .await;
+    };
+
+    let ledger = crate::sponsors::cached_ledger(&state.env, &state.database).await?;
+    let evaluation = crate::sponsors::evaluate(&ledger, chrono::Utc::now());
+
+    crate::sponsors::apply_sponsor_roles(
+        state,
+        &roles,
+        guild_id,
+        discord_id,
+        crate::sponsors::linked_sponsor(&evaluation, link),
+    )
+    .await
+}
+
+/// Link a GitHub account, run it again once the code is in your GitHub bio.
+#[poise::command(slash_command, rename = "link")]
+pub async fn sponsor_link_command(
+    ctx: crate::BotContext<'_>,
+    #[description = "Your GitHub username"] github: String,
+) -> Result<(), anyhow::Error> {
+    let Some(client) = crate::github::client(&ctx.data().env)? else {
+        ctx.send(
+            CreateReply::default()
+                .content("GitHub is not configured.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    ctx.defer_ephemeral().await?;
+
+    let login = github.trim().trim_start_matches('@');
+    let discord_id = ctx.author().id.get();
+
+    let Some(user) = crate::github::user(&client, login).await? else {
+        ctx.send(
+            CreateReply::default()
+                .content(format!("GitHub user `{login}` not found."))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let link = match crate::models::get_sponsor_link(ctx.data().database.read(), discord_id).await?
+    {
+        Some(link) if link.github_login.eq_ignore_ascii_case(&user.login) => link,
+        previous => {
+            let code = format!(
+                "calagopus-{}",
+                &hex::encode(sha2::Sha256::digest(format!(
+                    "{discord_id}:{}:{}",
+                    user.login,
+                    chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+                )))[..12]
+            );
+
+            let link = crate::models::create_pending_sponsor_link(
+                ctx.data().database.write(),
+                discord_id,
+                &user.login,
+                &code,
+            )
+            .await?;
+
+            // the role sync skips unverified links, so roles of the old account would stay forever
+            if previous.is_some_and(|previous| previous.github_id.is_some())
+                && let Err(err) = sync_member_roles(&ctx.data(), discord_id, None).await
+            {
+                tracing::error!("failed to remove sponsor roles of {discord_id}: {:?}", err);
+            }
+
+            link
+        }
+    };
+
+    if link.github_id == Some(user.id) {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "Your account is already linked to [**{login}**](https://github.com/{login}).",
+                    login = user.login
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if !user.bio.as_deref().unwrap_or_default().contains(&link.code) {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "Add `{}` to the bio of [**{login}**](https://github.com/{login}) and run this command again, you can remove it afterwards.",
+                    link.code,
+                    login = user.login
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if let Some(other) =
+        crate::models::get_sponsor_link_by_github_id(ctx.data().database.read(), user.id).await?
+        && other.discord_id != link.discord_id
+    {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "[**{login}**](https://github.com/{login}) is already linked to another Discord account.",
+                    login = user.login
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    crate::models::verify_sponsor_link(ctx.data().database.write(), link.id, user.id, &user.login)
+        .await?;
+
+    let link = crate::models::SponsorLink {
+        github_id: Some(user.id),
+        github_login: user.login.clone(),
+        ..link
+    };
+    if let Err(err) = sync_member_roles(&ctx.data(), discord_id, Some(&link)).await {
+        tracing::error!("failed to sync sponsor roles of {discord_id}: {:?}", err);
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Linked to [**{login}**](https://github.com/{login}), your sponsor roles have been updated.",
+                login = user.login
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Unlink your GitHub account and remove your sponsor roles.
+#[poise::command(slash_command, rename = "unlink")]
+pub async fn sponsor_unlink_command(ctx: crate::BotContext<'_>) -> Result<(), anyhow::Error> {
+    let discord_id = ctx.author().id.get();
+
+    let Some(link) =
+        crate::models::delete_sponsor_link(ctx.data().database.write(), discord_id).await?
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("Your account is not linked.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    if link.github_id.is_some()
+        && let Err(err) = sync_member_roles(&ctx.data(), discord_id, None).await
+    {
+        tracing::error!("failed to remove sponsor roles of {discord_id}: {:?}", err);
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Unlinked from [**{login}**](https://github.com/{login}).",
+                login = link.github_login
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use poise::CreateReply;
+use serenity::all::MessageFlags;
+
+/// Show sponsor stats and the leaderboard.
+#[poise::command(slash_command, rename = "sponsors")]
+pub async fn sponsors_command(ctx: crate::BotContext<'_>) -> Result<(), anyhow::Error> {
+    if !crate::github::configured(&ctx.data().env) {
+        ctx.send(
+            CreateReply::default()
+                .content("GitHub is not configured.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    ctx.defer().await?;
+
+    let ledger = crate::sponsors::cached_ledger(&ctx.data().env, &ctx.data().database).await?;
+    let evaluation = crate::sponsors::evaluate(&ledger, chrono::Utc::now());
+
+    ctx.send(
+        CreateReply::default()
+            .components(crate::sponsors::leaderboard_components(
+                &ctx.data().env,
+                &evaluation,
+                0,
+            ))
+            .flags(MessageFlags::IS_COMPONENTS_V2),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+mod sponsors_leaderboard;
 mod text_message_roles;
 
 #[async_trait::async_trait]
@@ -44,4 +45,5 @@ impl ComponentList {
 
 pub fn components(list: ComponentList) -> ComponentList {
     list.add_component(text_message_roles::TextMessageRoles)
+        .add_component(sponsors_leaderboard::SponsorsLeaderboard)
 }
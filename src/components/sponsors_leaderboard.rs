@@ -0,0 +1,51 @@
+use serenity::all::{ComponentInteraction, CreateInteractionResponse, EditInteractionResponse};
+
+pub struct SponsorsLeaderboard;
+
+#[async_trait::async_trait]
+impl crate::components::Component for SponsorsLeaderboard {
+    async fn execute(
+        &self,
+        state: &crate::routes::State,
+        ctx: &serenity::prelude::Context,
+        interaction: &ComponentInteraction,
+    ) -> Result<Option<()>, anyhow::Error> {
+        let Some(page) = interaction
+            .data
+            .custom_id
+            .strip_prefix(crate::sponsors::LEADERBOARD_PREVIOUS_PREFIX)
+            .or_else(|| {
+                interaction
+                    .data
+                    .custom_id
+                    .strip_prefix(crate::sponsors::LEADERBOARD_NEXT_PREFIX)
+            })
+        else {
+            return Ok(None);
+        };
+        let Ok(page) = page.parse::<usize>() else {
+            return Ok(None);
+        };
+
+        // fetching the ledger can take longer than discord waits for a response
+        interaction
+            .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+            .await?;
+
+        let ledger = crate::sponsors::cached_ledger(&state.env, &state.database).await?;
+        let evaluation = crate::sponsors::evaluate(&ledger, chrono::Utc::now());
+
+        interaction
+            .edit_response(
+                &ctx.http,
+                EditInteractionResponse::new().components(crate::sponsors::leaderboard_components(
+                    &state.env,
+                    &evaluation,
+                    page,
+                )),
+            )
+            .await?;
+
+        Ok(Some(()))
+    }
+}
@@ -1,6 +1,33 @@
 use dotenvy::dotenv;
 use tracing_subscriber::fmt::writer::MakeWriterExt;
 
+/// A webhook mounted at `/api/github/{name}` with its own secrets and fallback channel
+#[derive(Debug, Clone)]
+pub struct GithubSource {
+    pub name: String,
+    pub verify_tokens: Vec<String>,
+    pub channel_id: Option<u64>,
+}
+
+/// Credentials of the GitHub App the bot acts as, the key is read when the first client is built
+#[derive(Debug, Clone)]
+pub struct GithubApp {
+    pub app_id: u64,
+    pub installation_id: u64,
+    pub private_key_path: String,
+}
+
+/// Several secrets may be valid at once while one is being rotated
+fn split_verify_tokens(tokens: &str) -> Vec<String> {
+    tokens
+        .trim_matches('"')
+        .split(',')
+        .map(|token| token.trim())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_string())
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub struct Env {
     pub sentry_url: Option<String>,
@@ -13,9 +40,23 @@ pub struct Env {
 
     pub github_channel_id: u64,
     pub github_sponsors_channel_id: Option<u64>,
+    pub github_security_channel_id: Option<u64>,
+    pub github_admin_channel_id: Option<u64>,
+    pub github_discussions_forum_channel_id: Option<u64>,
     pub github_sponsors_login: Option<String>,
-    pub github_verify_token: String,
+    pub github_sponsors_guild_id: Option<u64>,
+    pub github_sponsors_role_grace: chrono::Duration,
+    pub github_verify_tokens: Vec<String>,
+    pub github_sources: Vec<GithubSource>,
+    pub github_replay_token: Option<String>,
+    pub github_delivery_retention: chrono::Duration,
     pub github_token: Option<String>,
+    pub github_app: Option<GithubApp>,
+    pub github_threads: bool,
+    pub github_workflow_run_messages: bool,
+    pub github_push_coalesce_seconds: u64,
+    pub github_star_milestones: Vec<u64>,
+    pub github_star_digest: Option<chrono::Duration>,
 
     pub bot_token: String,
 
@@ -68,16 +109,121 @@ impl Env {
                 .unwrap_or("".to_string())
                 .parse()
                 .ok(),
+            github_security_channel_id: std::env::var("GITHUB_SECURITY_CHANNEL_ID")
+                .map(|c| c.trim_matches('"').to_string())
+                .unwrap_or("".to_string())
+                .parse()
+                .ok(),
+            github_admin_channel_id: std::env::var("GITHUB_ADMIN_CHANNEL_ID")
+                .map(|c| c.trim_matches('"').to_string())
+                .unwrap_or("".to_string())
+                .parse()
+                .ok(),
+            github_discussions_forum_channel_id: std::env::var(
+                "GITHUB_DISCUSSIONS_FORUM_CHANNEL_ID",
+            )
+            .map(|c| c.trim_matches('"').to_string())
+            .unwrap_or("".to_string())
+            .parse()
+            .ok(),
             github_sponsors_login: std::env::var("GITHUB_SPONSORS_LOGIN")
                 .ok()
                 .map(|s| s.trim_matches('"').to_string()),
-            github_verify_token: std::env::var("GITHUB_VERIFY_TOKEN")
-                .expect("GITHUB_VERIFY_TOKEN is required")
+            github_sponsors_guild_id: std::env::var("GITHUB_SPONSORS_GUILD_ID")
+                .map(|c| c.trim_matches('"').to_string())
+                .unwrap_or("".to_string())
+                .parse()
+                .ok(),
+            github_sponsors_role_grace: chrono::Duration::days(
+                std::env::var("GITHUB_SPONSORS_ROLE_GRACE_DAYS")
+                    .map(|d| d.trim_matches('"').to_string())
+                    .unwrap_or("7".to_string())
+                    .parse()
+                    .expect("GITHUB_SPONSORS_ROLE_GRACE_DAYS must be a number"),
+            ),
+            github_verify_tokens: split_verify_tokens(
+                &std::env::var("GITHUB_VERIFY_TOKEN").expect("GITHUB_VERIFY_TOKEN is required"),
+            ),
+            github_sources: std::env::var("GITHUB_SOURCES")
+                .unwrap_or("".to_string())
                 .trim_matches('"')
-                .to_string(),
+                .split(',')
+                .map(|name| name.trim())
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    let prefix = format!("GITHUB_SOURCE_{}", name.to_uppercase().replace('-', "_"));
+
+                    GithubSource {
+                        name: name.to_string(),
+                        verify_tokens: split_verify_tokens(
+                            &std::env::var(format!("{prefix}_VERIFY_TOKEN"))
+                                .unwrap_or_else(|_| panic!("{prefix}_VERIFY_TOKEN is required")),
+                        ),
+                        channel_id: std::env::var(format!("{prefix}_CHANNEL_ID"))
+                            .ok()
+                            .and_then(|c| c.trim_matches('"').parse().ok()),
+                    }
+                })
+                .collect(),
+            github_replay_token: std::env::var("GITHUB_REPLAY_TOKEN")
+                .ok()
+                .map(|s| s.trim_matches('"').to_string())
+                .filter(|s| !s.is_empty()),
+            github_delivery_retention: chrono::Duration::days(
+                std::env::var("GITHUB_DELIVERY_RETENTION_DAYS")
+                    .map(|d| d.trim_matches('"').to_string())
+                    .unwrap_or("7".to_string())
+                    .parse()
+                    .expect("GITHUB_DELIVERY_RETENTION_DAYS must be a number"),
+            ),
             github_token: std::env::var("GITHUB_TOKEN")
                 .ok()
                 .map(|s| s.trim_matches('"').to_string()),
+            github_app: std::env::var("GITHUB_APP_ID")
+                .ok()
+                .map(|s| s.trim_matches('"').to_string())
+                .filter(|s| !s.is_empty())
+                .map(|app_id| GithubApp {
+                    app_id: app_id.parse().expect("GITHUB_APP_ID must be a number"),
+                    installation_id: std::env::var("GITHUB_APP_INSTALLATION_ID")
+                        .expect("GITHUB_APP_INSTALLATION_ID is required with GITHUB_APP_ID")
+                        .trim_matches('"')
+                        .parse()
+                        .expect("GITHUB_APP_INSTALLATION_ID must be a number"),
+                    private_key_path: std::env::var("GITHUB_APP_PRIVATE_KEY_PATH")
+                        .expect("GITHUB_APP_PRIVATE_KEY_PATH is required with GITHUB_APP_ID")
+                        .trim_matches('"')
+                        .to_string(),
+                }),
+            github_threads: std::env::var("GITHUB_THREADS")
+                .unwrap_or("true".to_string())
+                .trim_matches('"')
+                .parse()
+                .unwrap(),
+            github_workflow_run_messages: std::env::var("GITHUB_WORKFLOW_RUN_MESSAGES")
+                .unwrap_or("false".to_string())
+                .trim_matches('"')
+                .parse()
+                .unwrap(),
+            github_push_coalesce_seconds: std::env::var("GITHUB_PUSH_COALESCE_SECONDS")
+                .unwrap_or("0".to_string())
+                .trim_matches('"')
+                .parse()
+                .unwrap(),
+            github_star_milestones: std::env::var("GITHUB_STAR_MILESTONES")
+                .unwrap_or("".to_string())
+                .trim_matches('"')
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect(),
+            github_star_digest: match std::env::var("GITHUB_STAR_DIGEST")
+                .unwrap_or("".to_string())
+                .trim_matches('"')
+            {
+                "daily" => Some(chrono::Duration::days(1)),
+                "weekly" => Some(chrono::Duration::weeks(1)),
+                _ => None,
+            },
 
             bot_token: std::env::var("BOT_TOKEN")
                 .expect("BOT_TOKEN is required")
@@ -0,0 +1,106 @@
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+static CLIENT: OnceLock<octocrab::Octocrab> = OnceLock::new();
+
+/// Whether a GitHub App or `GITHUB_TOKEN` is configured
+#[inline]
+pub fn configured(env: &crate::env::Env) -> bool {
+    env.github_app.is_some() || env.github_token.as_ref().is_some_and(|t| !t.is_empty())
+}
+
+/// Client authenticated as the app itself, only used to create the installation client
+fn app_client(app: &crate::env::GithubApp) -> Result<octocrab::Octocrab, anyhow::Error> {
+    let private_key = std::fs::read(&app.private_key_path)?;
+
+    let client = octocrab::Octocrab::builder()
+        .app(
+            octocrab::models::AppId(app.app_id),
+            jsonwebtoken::EncodingKey::from_rsa_pem(&private_key)?,
+        )
+        .build()?;
+
+    Ok(client)
+}
+
+/// Shared REST and GraphQL client, `None` if neither a GitHub App nor `GITHUB_TOKEN` is configured.
+/// App clients refresh their installation token on their own.
+pub fn client(env: &crate::env::Env) -> Result<Option<octocrab::Octocrab>, anyhow::Error> {
+    if let Some(client) = CLIENT.get() {
+        return Ok(Some(client.clone()));
+    }
+
+    let client = if let Some(app) = &env.github_app {
+        app_client(app)?.installation(octocrab::models::InstallationId(app.installation_id))?
+    } else if let Some(github_token) = env.github_token.as_ref().filter(|t| !t.is_empty()) {
+        octocrab::Octocrab::builder()
+            .personal_token(github_token.clone())
+            .build()?
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(CLIENT.get_or_init(|| client).clone()))
+}
+
+/// Name of the first step that failed in an actions job
+pub async fn failed_job_step(
+    client: &octocrab::Octocrab,
+    repository: &str,
+    job_id: i64,
+) -> Result<Option<String>, anyhow::Error> {
+    #[derive(Deserialize)]
+    struct JobStep {
+        name: String,
+        conclusion: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct Job {
+        steps: Vec<JobStep>,
+    }
+
+    let job: Job = client
+        .get(
+            format!("/repos/{repository}/actions/jobs/{job_id}"),
+            None::<&()>,
+        )
+        .await?;
+
+    Ok(job
+        .steps
+        .into_iter()
+        .find(|step| matches!(step.conclusion.as_deref(), Some("failure" | "timed_out")))
+        .map(|step| step.name))
+}
+
+#[derive(Deserialize)]
+pub struct GithubUser {
+    pub id: i64,
+    pub login: String,
+    pub bio: Option<String>,
+}
+
+/// Public profile of a user, `None` if no such user exists.
+/// Logins are only letters, digits and hyphens, anything else would change the request path.
+pub async fn user(
+    client: &octocrab::Octocrab,
+    login: &str,
+) -> Result<Option<GithubUser>, anyhow::Error> {
+    if login.is_empty() || !login.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Ok(None);
+    }
+
+    match client
+        .get::<GithubUser, _, _>(format!("/users/{login}"), None::<&()>)
+        .await
+    {
+        Ok(user) => Ok(Some(user)),
+        Err(octocrab::Error::GitHub { source, .. })
+            if source.status_code == axum::http::StatusCode::NOT_FOUND =>
+        {
+            Ok(None)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
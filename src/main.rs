@@ -24,8 +24,10 @@ mod components;
 mod database;
 mod env;
 mod events;
+mod github;
 mod modals;
 mod models;
+mod renderers;
 mod response;
 mod routes;
 mod sponsors;
@@ -89,6 +91,7 @@ async fn main() {
 
         env: env.clone(),
         database: Arc::new(database::Database::new(env.clone()).await),
+        renderers: renderers::renderers(renderers::EventRendererList::new()),
         bot: RwLock::new(Arc::new(serenity::http::Http::new(
             Token::from_str(&env.bot_token).unwrap(),
         ))),
@@ -147,6 +150,9 @@ async fn main() {
     });
 
     sponsors::spawn_sponsor_updates_task(state.clone());
+    sponsors::spawn_sponsor_roles_task(state.clone());
+    routes::github::spawn_github_events_task(state.clone());
+    routes::github::spawn_github_star_digest_task(state.clone());
 
     let app = OpenApiRouter::new()
         .nest("/api", routes::router(&state))
@@ -0,0 +1,60 @@
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+/// A discussion that was forwarded into a forum post
+#[derive(Debug)]
+pub struct GithubDiscussion {
+    pub id: i64,
+    pub repository_id: i64,
+    pub number: i64,
+    pub channel_id: i64,
+    pub thread_id: i64,
+}
+
+impl FromRow<'_, SqliteRow> for GithubDiscussion {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            repository_id: row.try_get("repository_id")?,
+            number: row.try_get("number")?,
+            channel_id: row.try_get("channel_id")?,
+            thread_id: row.try_get("thread_id")?,
+        })
+    }
+}
+
+pub async fn get_github_discussion(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    number: i64,
+) -> Result<Option<GithubDiscussion>, anyhow::Error> {
+    let discussion = sqlx::query_as::<_, GithubDiscussion>(
+        "SELECT * FROM github_discussions WHERE repository_id = ? AND number = ?",
+    )
+    .bind(repository_id)
+    .bind(number)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(discussion)
+}
+
+pub async fn create_github_discussion(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    number: i64,
+    channel_id: u64,
+    thread_id: u64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "INSERT INTO github_discussions (repository_id, number, channel_id, thread_id) VALUES (?, ?, ?, ?)
+         ON CONFLICT (repository_id, number) DO UPDATE SET channel_id = excluded.channel_id, thread_id = excluded.thread_id",
+    )
+    .bind(repository_id)
+    .bind(number)
+    .bind(channel_id as i64)
+    .bind(thread_id as i64)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+#[derive(Debug)]
+pub struct GithubIssue {
+    pub id: i64,
+    pub repository_id: i64,
+    pub number: i64,
+    pub channel_id: i64,
+    pub message_id: i64,
+    pub thread_id: Option<i64>,
+
+    pub closed: bool,
+}
+
+impl FromRow<'_, SqliteRow> for GithubIssue {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            repository_id: row.try_get("repository_id")?,
+            number: row.try_get("number")?,
+            channel_id: row.try_get("channel_id")?,
+            message_id: row.try_get("message_id")?,
+            thread_id: row.try_get("thread_id")?,
+            closed: row.try_get("closed")?,
+        })
+    }
+}
+
+pub async fn get_github_issue(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    number: i64,
+) -> Result<Option<GithubIssue>, anyhow::Error> {
+    let issue = sqlx::query_as::<_, GithubIssue>(
+        "SELECT * FROM github_issues WHERE repository_id = ? AND number = ?",
+    )
+    .bind(repository_id)
+    .bind(number)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(issue)
+}
+
+/// The thread is opened after the message is saved, see [`set_github_issue_thread`]
+pub async fn create_github_issue(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    number: i64,
+    channel_id: u64,
+    message_id: u64,
+) -> Result<GithubIssue, anyhow::Error> {
+    let issue = sqlx::query_as::<_, GithubIssue>(
+        "INSERT INTO github_issues (repository_id, number, channel_id, message_id) VALUES (?, ?, ?, ?)
+         ON CONFLICT (repository_id, number) DO UPDATE SET channel_id = excluded.channel_id, message_id = excluded.message_id, thread_id = NULL, closed = false
+         RETURNING *",
+    )
+    .bind(repository_id)
+    .bind(number)
+    .bind(channel_id as i64)
+    .bind(message_id as i64)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(issue)
+}
+
+/// Points an issue at the message sent to replace its deleted one, keeping its thread
+pub async fn set_github_issue_message(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    channel_id: u64,
+    message_id: u64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query("UPDATE github_issues SET channel_id = ?, message_id = ? WHERE id = ?")
+        .bind(channel_id as i64)
+        .bind(message_id as i64)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn set_github_issue_thread(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    thread_id: u64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query("UPDATE github_issues SET thread_id = ? WHERE id = ?")
+        .bind(thread_id as i64)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn set_github_issue_closed(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    closed: bool,
+) -> Result<(), anyhow::Error> {
+    sqlx::query("UPDATE github_issues SET closed = ? WHERE id = ?")
+        .bind(closed)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
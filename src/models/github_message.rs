@@ -3,28 +3,61 @@ use octocrab::models::webhook_events::payload::PushWebhookEventCommit;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Row, sqlite::SqliteRow};
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowSource {
+    #[default]
+    Actions,
+    CheckRun,
+    CheckSuite,
+    Status,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WorkflowStatus {
     pub name: String,
     pub status: octocrab::models::workflows::Status,
     pub started: DateTime<chrono::Utc>,
+
+    #[serde(default)]
+    pub source: WorkflowSource,
+    /// The check app, `None` for actions and commit statuses
+    #[serde(default)]
+    pub app: Option<String>,
+
+    #[serde(default)]
+    pub completed: Option<DateTime<chrono::Utc>>,
+    /// GitHub's conclusion, e.g. `success`, `cancelled` or `timed_out`
+    #[serde(default)]
+    pub conclusion: Option<String>,
+    #[serde(default)]
+    pub failed_step: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct GithubMessage {
     pub id: i64,
+    pub channel_id: Option<i64>,
     pub message_id: i64,
 
     pub commits: Vec<PushWebhookEventCommit>,
     pub branch: String,
+    /// Replaces the commit list for messages that do not belong to a push, like scheduled runs
+    pub title: Option<String>,
+    /// Set once any of the coalesced pushes was a force-push
+    pub forced: bool,
 
-    pub workflow_status: indexmap::IndexMap<i64, WorkflowStatus>,
+    /// Commit the first coalesced push started from, `None` for new branches
+    pub before_sha: Option<String>,
+    pub workflow_sha: String,
+    pub workflow_status: indexmap::IndexMap<String, WorkflowStatus>,
 }
 
 impl FromRow<'_, SqliteRow> for GithubMessage {
     fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
         Ok(Self {
             id: row.try_get("id")?,
+            channel_id: row.try_get("channel_id")?,
             message_id: row.try_get("message_id")?,
             commits: serde_json::from_str(&row.try_get::<String, _>("commits")?).map_err(|e| {
                 sqlx::Error::ColumnDecode {
@@ -33,6 +66,10 @@ impl FromRow<'_, SqliteRow> for GithubMessage {
                 }
             })?,
             branch: row.try_get("branch")?,
+            title: row.try_get("title")?,
+            forced: row.try_get("forced")?,
+            before_sha: row.try_get("before_sha")?,
+            workflow_sha: row.try_get("workflow_sha")?,
             workflow_status: serde_json::from_str(&row.try_get::<String, _>("workflow_status")?)
                 .map_err(|e| sqlx::Error::ColumnDecode {
                     index: "workflow_status".into(),
@@ -41,3 +78,59 @@ impl FromRow<'_, SqliteRow> for GithubMessage {
         })
     }
 }
+
+/// Latest push message on a branch that was pushed to within `window`
+pub async fn recent_github_push_message(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    branch: &str,
+    window: chrono::Duration,
+) -> Result<Option<GithubMessage>, anyhow::Error> {
+    let message = sqlx::query_as::<_, GithubMessage>(
+        "SELECT * FROM github_messages WHERE repository_id = ? AND branch = ? AND title IS NULL AND COALESCE(updated, created) >= ? ORDER BY id DESC LIMIT 1",
+    )
+    .bind(repository_id)
+    .bind(branch)
+    .bind((chrono::Utc::now() - window).timestamp())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(message)
+}
+
+/// Points a tracked message at the one sent to replace it after it was deleted
+pub async fn set_github_message_id(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    channel_id: u64,
+    message_id: u64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query("UPDATE github_messages SET channel_id = ?, message_id = ? WHERE id = ?")
+        .bind(channel_id as i64)
+        .bind(message_id as i64)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Saves a push message after another push was folded into it
+pub async fn update_github_push_message(
+    pool: &sqlx::SqlitePool,
+    message: &GithubMessage,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE github_messages SET commits = ?, forced = ?, workflow_sha = ?, workflow_status = ?, updated = ? WHERE id = ?",
+    )
+    .bind(serde_json::to_string(&message.commits)?)
+    .bind(message.forced)
+    .bind(&message.workflow_sha)
+    .bind(serde_json::to_string(&message.workflow_status)?)
+    .bind(chrono::Utc::now().timestamp())
+    .bind(message.id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,319 @@
+use chrono::DateTime;
+use octocrab::models::{IssueState, pulls::ReviewState};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullRequestState {
+    Draft,
+    Open,
+    Merged,
+    Closed,
+}
+
+impl PullRequestState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Open => "open",
+            Self::Merged => "merged",
+            Self::Closed => "closed",
+        }
+    }
+
+    fn parse(state: &str) -> Option<Self> {
+        match state {
+            "draft" => Some(Self::Draft),
+            "open" => Some(Self::Open),
+            "merged" => Some(Self::Merged),
+            "closed" => Some(Self::Closed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PullRequestReview {
+    pub state: ReviewState,
+    pub submitted: DateTime<chrono::Utc>,
+}
+
+#[derive(Debug)]
+pub struct GithubPullRequest {
+    pub id: i64,
+    pub repository_id: i64,
+    pub number: i64,
+    pub channel_id: i64,
+    pub message_id: Option<i64>,
+    pub thread_id: Option<i64>,
+
+    pub state: PullRequestState,
+    pub title: String,
+    pub url: String,
+    pub author: String,
+    pub base_branch: String,
+    pub head_sha: String,
+
+    pub labels: Vec<String>,
+    pub requested_reviewers: Vec<String>,
+    pub reviews: indexmap::IndexMap<String, PullRequestReview>,
+
+    pub additions: i64,
+    pub deletions: i64,
+    pub changed_files: i64,
+
+    pub workflow_status: indexmap::IndexMap<String, super::WorkflowStatus>,
+}
+
+impl FromRow<'_, SqliteRow> for GithubPullRequest {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            repository_id: row.try_get("repository_id")?,
+            number: row.try_get("number")?,
+            channel_id: row.try_get("channel_id")?,
+            message_id: row.try_get("message_id")?,
+            thread_id: row.try_get("thread_id")?,
+            state: PullRequestState::parse(&row.try_get::<String, _>("state")?).ok_or_else(
+                || sqlx::Error::ColumnDecode {
+                    index: "state".into(),
+                    source: "unknown pull request state".into(),
+                },
+            )?,
+            title: row.try_get("title")?,
+            url: row.try_get("url")?,
+            author: row.try_get("author")?,
+            base_branch: row.try_get("base_branch")?,
+            head_sha: row.try_get("head_sha")?,
+            labels: serde_json::from_str(&row.try_get::<String, _>("labels")?).map_err(|e| {
+                sqlx::Error::ColumnDecode {
+                    index: "labels".into(),
+                    source: Box::new(e),
+                }
+            })?,
+            requested_reviewers: serde_json::from_str(
+                &row.try_get::<String, _>("requested_reviewers")?,
+            )
+            .map_err(|e| sqlx::Error::ColumnDecode {
+                index: "requested_reviewers".into(),
+                source: Box::new(e),
+            })?,
+            reviews: serde_json::from_str(&row.try_get::<String, _>("reviews")?).map_err(|e| {
+                sqlx::Error::ColumnDecode {
+                    index: "reviews".into(),
+                    source: Box::new(e),
+                }
+            })?,
+            additions: row.try_get("additions")?,
+            deletions: row.try_get("deletions")?,
+            changed_files: row.try_get("changed_files")?,
+            workflow_status: serde_json::from_str(&row.try_get::<String, _>("workflow_status")?)
+                .map_err(|e| sqlx::Error::ColumnDecode {
+                    index: "workflow_status".into(),
+                    source: Box::new(e),
+                })?,
+        })
+    }
+}
+
+impl GithubPullRequest {
+    /// An empty row that is never saved, used by dry runs for pull requests the bot does not track
+    pub fn unsaved(repository_id: i64, number: i64, channel_id: u64) -> Self {
+        Self {
+            id: 0,
+            repository_id,
+            number,
+            channel_id: channel_id as i64,
+            message_id: None,
+            thread_id: None,
+            state: PullRequestState::Open,
+            title: String::new(),
+            url: String::new(),
+            author: String::new(),
+            base_branch: String::new(),
+            head_sha: String::new(),
+            labels: Vec::new(),
+            requested_reviewers: Vec::new(),
+            reviews: indexmap::IndexMap::new(),
+            additions: 0,
+            deletions: 0,
+            changed_files: 0,
+            workflow_status: indexmap::IndexMap::new(),
+        }
+    }
+
+    /// Copies everything the message shows from a webhook's pull request object.
+    /// Workflow status belongs to the head commit, so it is reset when new commits are pushed.
+    pub fn apply_pull_request(&mut self, pull_request: &octocrab::models::pulls::PullRequest) {
+        self.state = if pull_request.merged_at.is_some() {
+            PullRequestState::Merged
+        } else if pull_request.state == Some(IssueState::Closed) {
+            PullRequestState::Closed
+        } else if pull_request.draft == Some(true) {
+            PullRequestState::Draft
+        } else {
+            PullRequestState::Open
+        };
+
+        self.title = pull_request.title.clone().unwrap_or_default();
+        self.url = pull_request
+            .html_url
+            .as_ref()
+            .map_or_else(|| "".to_string(), |url| url.to_string());
+        if let Some(user) = &pull_request.user {
+            self.author = user.login.clone();
+        }
+        self.base_branch = pull_request.base.ref_field.clone();
+
+        if self.head_sha != pull_request.head.sha {
+            self.head_sha = pull_request.head.sha.clone();
+            self.workflow_status.clear();
+        }
+
+        self.labels = pull_request
+            .labels
+            .iter()
+            .flatten()
+            .map(|label| label.name.clone())
+            .collect();
+        self.requested_reviewers = pull_request
+            .requested_reviewers
+            .iter()
+            .flatten()
+            .map(|reviewer| reviewer.login.clone())
+            .chain(
+                pull_request
+                    .requested_teams
+                    .iter()
+                    .flatten()
+                    .map(|team| format!("@{}", team.name)),
+            )
+            .collect();
+
+        if let Some(additions) = pull_request.additions {
+            self.additions = additions as i64;
+        }
+        if let Some(deletions) = pull_request.deletions {
+            self.deletions = deletions as i64;
+        }
+        if let Some(changed_files) = pull_request.changed_files {
+            self.changed_files = changed_files as i64;
+        }
+    }
+
+    /// Keeps the latest verdict per reviewer, plain comments do not override an earlier verdict
+    pub fn record_review(&mut self, reviewer: &str, state: ReviewState) {
+        match state {
+            ReviewState::Dismissed => {
+                self.reviews.shift_remove(reviewer);
+            }
+            ReviewState::Commented if self.reviews.contains_key(reviewer) => {}
+            ReviewState::Approved | ReviewState::ChangesRequested | ReviewState::Commented => {
+                self.reviews.insert(
+                    reviewer.to_string(),
+                    PullRequestReview {
+                        state,
+                        submitted: chrono::Utc::now(),
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+pub async fn get_github_pull_request(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    number: i64,
+) -> Result<Option<GithubPullRequest>, anyhow::Error> {
+    let pull_request = sqlx::query_as::<_, GithubPullRequest>(
+        "SELECT * FROM github_pull_requests WHERE repository_id = ? AND number = ?",
+    )
+    .bind(repository_id)
+    .bind(number)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(pull_request)
+}
+
+pub async fn github_pull_requests_by_head_sha(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    head_sha: &str,
+) -> Result<Vec<GithubPullRequest>, anyhow::Error> {
+    let pull_requests = sqlx::query_as::<_, GithubPullRequest>(
+        "SELECT * FROM github_pull_requests WHERE repository_id = ? AND head_sha = ? AND state IN ('draft', 'open')",
+    )
+    .bind(repository_id)
+    .bind(head_sha)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(pull_requests)
+}
+
+/// Creates an empty row, the caller is expected to fill it using [`GithubPullRequest::apply_pull_request`]
+pub async fn create_github_pull_request(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    number: i64,
+    channel_id: u64,
+) -> Result<GithubPullRequest, anyhow::Error> {
+    let pull_request = sqlx::query_as::<_, GithubPullRequest>(
+        "INSERT INTO github_pull_requests (repository_id, number, channel_id, state, title, url, author, base_branch, head_sha, labels, requested_reviewers, reviews, workflow_status)
+         VALUES (?, ?, ?, 'open', '', '', '', '', '', '[]', '[]', '{}', '{}')
+         RETURNING *",
+    )
+    .bind(repository_id)
+    .bind(number)
+    .bind(channel_id as i64)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(pull_request)
+}
+
+pub async fn update_github_pull_request(
+    pool: &sqlx::SqlitePool,
+    pull_request: &GithubPullRequest,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE github_pull_requests SET message_id = ?, thread_id = ?, state = ?, title = ?, url = ?, author = ?, base_branch = ?, head_sha = ?, labels = ?, requested_reviewers = ?, reviews = ?, additions = ?, deletions = ?, changed_files = ?, workflow_status = ? WHERE id = ?",
+    )
+    .bind(pull_request.message_id)
+    .bind(pull_request.thread_id)
+    .bind(pull_request.state.as_str())
+    .bind(&pull_request.title)
+    .bind(&pull_request.url)
+    .bind(&pull_request.author)
+    .bind(&pull_request.base_branch)
+    .bind(&pull_request.head_sha)
+    .bind(serde_json::to_string(&pull_request.labels)?)
+    .bind(serde_json::to_string(&pull_request.requested_reviewers)?)
+    .bind(serde_json::to_string(&pull_request.reviews)?)
+    .bind(pull_request.additions)
+    .bind(pull_request.deletions)
+    .bind(pull_request.changed_files)
+    .bind(serde_json::to_string(&pull_request.workflow_status)?)
+    .bind(pull_request.id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_github_pull_request_thread(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    thread_id: u64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query("UPDATE github_pull_requests SET thread_id = ? WHERE id = ?")
+        .bind(thread_id as i64)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use futures::TryStreamExt;
+use serenity::all::CreateAutocompleteResponse;
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+#[derive(Debug)]
+pub struct GithubPushFilter {
+    pub id: i64,
+    pub repository: String,
+
+    pub allow_branches: Vec<String>,
+    pub deny_branches: Vec<String>,
+    pub ignore_bots: bool,
+}
+
+impl FromRow<'_, SqliteRow> for GithubPushFilter {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            repository: row.try_get("repository")?,
+            allow_branches: serde_json::from_str(&row.try_get::<String, _>("allow_branches")?)
+                .map_err(|e| sqlx::Error::ColumnDecode {
+                    index: "allow_branches".into(),
+                    source: Box::new(e),
+                })?,
+            deny_branches: serde_json::from_str(&row.try_get::<String, _>("deny_branches")?)
+                .map_err(|e| sqlx::Error::ColumnDecode {
+                    index: "deny_branches".into(),
+                    source: Box::new(e),
+                })?,
+            ignore_bots: row.try_get("ignore_bots")?,
+        })
+    }
+}
+
+impl GithubPushFilter {
+    /// `branch` is `None` for tag pushes, which branch patterns do not apply to
+    pub fn allows(&self, branch: Option<&str>, sender_is_bot: bool) -> bool {
+        if self.ignore_bots && sender_is_bot {
+            return false;
+        }
+
+        let Some(branch) = branch else {
+            return true;
+        };
+
+        if !self.allow_branches.is_empty()
+            && !self
+                .allow_branches
+                .iter()
+                .any(|pattern| crate::utils::glob_matches(pattern, branch))
+        {
+            return false;
+        }
+
+        !self
+            .deny_branches
+            .iter()
+            .any(|pattern| crate::utils::glob_matches(pattern, branch))
+    }
+
+    pub fn describe(&self) -> String {
+        let patterns = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| format!("`{pattern}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut description = format!("`{}`", self.repository);
+
+        if !self.allow_branches.is_empty() {
+            description.push_str(&format!(" allow {}", patterns(&self.allow_branches)));
+        }
+        if !self.deny_branches.is_empty() {
+            description.push_str(&format!(" deny {}", patterns(&self.deny_branches)));
+        }
+        if self.ignore_bots {
+            description.push_str(" ignoring bots");
+        }
+
+        description
+    }
+}
+
+pub async fn all_github_push_filters(
+    pool: &sqlx::SqlitePool,
+) -> Result<Vec<GithubPushFilter>, anyhow::Error> {
+    let filters =
+        sqlx::query_as::<_, GithubPushFilter>("SELECT * FROM github_push_filters ORDER BY id ASC")
+            .fetch_all(pool)
+            .await?;
+
+    Ok(filters)
+}
+
+/// Every filter whose repository glob matches has to allow the push
+pub async fn github_push_allowed(
+    pool: &sqlx::SqlitePool,
+    repository: &str,
+    branch: Option<&str>,
+    sender_is_bot: bool,
+) -> Result<bool, anyhow::Error> {
+    let filters = all_github_push_filters(pool).await?;
+
+    Ok(filters
+        .iter()
+        .filter(|filter| crate::utils::glob_matches(&filter.repository, repository))
+        .all(|filter| filter.allows(branch, sender_is_bot)))
+}
+
+pub async fn autocomplete_github_push_filter_id<'a>(
+    ctx: crate::BotContext<'_>,
+    partial: &'a str,
+) -> serenity::all::CreateAutocompleteResponse<'a> {
+    let database = &ctx.data().database;
+    let mut filters = sqlx::query_as(
+        "SELECT * FROM github_push_filters WHERE repository LIKE ? ORDER BY created DESC LIMIT 25",
+    )
+    .bind(format!("%{}%", partial))
+    .fetch(database.read());
+
+    let mut response = CreateAutocompleteResponse::new();
+
+    while let Ok(Some(filter)) = filters.try_next().await {
+        let filter: GithubPushFilter = filter;
+        response = response.add_choice(serenity::all::AutocompleteChoice::new(
+            filter.repository,
+            filter.id as u64,
+        ));
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GithubPushFilter;
+
+    fn filter(
+        allow_branches: &[&str],
+        deny_branches: &[&str],
+        ignore_bots: bool,
+    ) -> GithubPushFilter {
+        GithubPushFilter {
+            id: 1,
+            repository: "calagopus/*".to_string(),
+            allow_branches: allow_branches.iter().map(|b| b.to_string()).collect(),
+            deny_branches: deny_branches.iter().map(|b| b.to_string()).collect(),
+            ignore_bots,
+        }
+    }
+
+    #[test]
+    fn allows() {
+        let cases = [
+            (filter(&[], &[], false), Some("main"), false, true),
+            (filter(&[], &[], false), Some("main"), true, true),
+            (filter(&[], &[], true), Some("main"), true, false),
+            (filter(&[], &[], true), Some("main"), false, true),
+            // bots are skipped even for tags
+            (filter(&[], &[], true), None, true, false),
+            // tags ignore branch patterns
+            (filter(&["main"], &[], false), None, false, true),
+            (filter(&[], &["*"], false), None, false, true),
+            (
+                filter(&["main", "release/*"], &[], false),
+                Some("main"),
+                false,
+                true,
+            ),
+            (
+                filter(&["main", "release/*"], &[], false),
+                Some("release/1.0"),
+                false,
+                true,
+            ),
+            (
+                filter(&["main", "release/*"], &[], false),
+                Some("feature/x"),
+                false,
+                false,
+            ),
+            (
+                filter(&[], &["dependabot/*"], false),
+                Some("dependabot/npm/axios"),
+                false,
+                false,
+            ),
+            (
+                filter(&[], &["dependabot/*"], false),
+                Some("main"),
+                false,
+                true,
+            ),
+            // deny patterns still apply to allowed branches
+            (
+                filter(&["release/*"], &["release/old-*"], false),
+                Some("release/old-1"),
+                false,
+                false,
+            ),
+            (
+                filter(&["release/*"], &["release/old-*"], false),
+                Some("release/2.0"),
+                false,
+                true,
+            ),
+            (filter(&["Main"], &[], false), Some("main"), false, true),
+        ];
+
+        for (filter, branch, sender_is_bot, expected) in cases {
+            assert_eq!(
+                filter.allows(branch, sender_is_bot),
+                expected,
+                "{} with {branch:?}, bot {sender_is_bot}",
+                filter.describe()
+            );
+        }
+    }
+}
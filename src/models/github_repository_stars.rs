@@ -0,0 +1,200 @@
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+#[derive(Debug)]
+pub struct GithubRepositoryStars {
+    pub id: i64,
+    pub repository_id: i64,
+    pub repository: String,
+    /// Webhook source of the latest event, its channel is where digests go by default
+    pub source: Option<String>,
+
+    pub stars: i64,
+    /// Highest milestone already announced, never lowered so unstars cannot re-trigger it
+    pub last_milestone: i64,
+
+    /// Stars and unstars since the last digest
+    pub starred: i64,
+    pub unstarred: i64,
+    pub digest_started: chrono::DateTime<chrono::Utc>,
+}
+
+impl FromRow<'_, SqliteRow> for GithubRepositoryStars {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            repository_id: row.try_get("repository_id")?,
+            repository: row.try_get("repository")?,
+            source: row.try_get("source")?,
+            stars: row.try_get("stars")?,
+            last_milestone: row.try_get("last_milestone")?,
+            starred: row.try_get("starred")?,
+            unstarred: row.try_get("unstarred")?,
+            digest_started: chrono::DateTime::<chrono::Utc>::from_timestamp(
+                row.try_get("digest_started")?,
+                0,
+            )
+            .unwrap_or_default(),
+        })
+    }
+}
+
+impl GithubRepositoryStars {
+    /// The highest of `milestones` the current count reached that was not announced yet
+    pub fn reached_milestone(&self, milestones: &[u64]) -> Option<i64> {
+        milestones
+            .iter()
+            .map(|milestone| *milestone as i64)
+            .filter(|milestone| *milestone <= self.stars)
+            .max()
+            .filter(|milestone| *milestone > self.last_milestone)
+    }
+}
+
+pub async fn get_github_repository_stars(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+) -> Result<Option<GithubRepositoryStars>, anyhow::Error> {
+    let repository_stars = sqlx::query_as::<_, GithubRepositoryStars>(
+        "SELECT * FROM github_repository_stars WHERE repository_id = ?",
+    )
+    .bind(repository_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(repository_stars)
+}
+
+/// Stores the payload's star count and counts the star or unstar towards the next digest.
+/// A repository seen for the first time starts at the highest of `milestones` it had already
+/// reached before this event, so only milestones crossed from here on are announced
+pub async fn record_github_star(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    repository: &str,
+    source: Option<&str>,
+    stars: i64,
+    starred: bool,
+    milestones: &[u64],
+) -> Result<GithubRepositoryStars, anyhow::Error> {
+    let previous_stars = if starred { stars - 1 } else { stars };
+    let last_milestone = milestones
+        .iter()
+        .map(|milestone| *milestone as i64)
+        .filter(|milestone| *milestone <= previous_stars)
+        .max()
+        .unwrap_or(0);
+
+    let repository_stars = sqlx::query_as::<_, GithubRepositoryStars>(
+        "INSERT INTO github_repository_stars (repository_id, repository, source, stars, last_milestone, starred, unstarred) VALUES (?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT (repository_id) DO UPDATE SET repository = excluded.repository, source = excluded.source, stars = excluded.stars, starred = starred + excluded.starred, unstarred = unstarred + excluded.unstarred
+         RETURNING *",
+    )
+    .bind(repository_id)
+    .bind(repository)
+    .bind(source)
+    .bind(stars)
+    .bind(last_milestone)
+    .bind(starred as i64)
+    .bind(!starred as i64)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(repository_stars)
+}
+
+pub async fn set_github_star_milestone(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    milestone: i64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query("UPDATE github_repository_stars SET last_milestone = ? WHERE id = ?")
+        .bind(milestone)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Repositories with star activity whose digest period started before `before`
+pub async fn due_github_star_digests(
+    pool: &sqlx::SqlitePool,
+    before: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<GithubRepositoryStars>, anyhow::Error> {
+    let repository_stars = sqlx::query_as::<_, GithubRepositoryStars>(
+        "SELECT * FROM github_repository_stars WHERE digest_started <= ? AND (starred > 0 OR unstarred > 0) ORDER BY repository ASC",
+    )
+    .bind(before.timestamp())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(repository_stars)
+}
+
+pub async fn reset_github_star_digest(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE github_repository_stars SET starred = 0, unstarred = 0, digest_started = ? WHERE id = ?",
+    )
+    .bind(chrono::Utc::now().timestamp())
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GithubRepositoryStars;
+
+    fn repository_stars(stars: i64, last_milestone: i64) -> GithubRepositoryStars {
+        GithubRepositoryStars {
+            id: 1,
+            repository_id: 1,
+            repository: "calagopus/panel".to_string(),
+            source: None,
+            stars,
+            last_milestone,
+            starred: 0,
+            unstarred: 0,
+            digest_started: chrono::DateTime::<chrono::Utc>::default(),
+        }
+    }
+
+    #[test]
+    fn reached_milestone() {
+        let milestones = [100, 500, 1000];
+
+        let cases = [
+            (0, 0, None),
+            (99, 0, None),
+            (100, 0, Some(100)),
+            (101, 0, Some(100)),
+            (100, 100, None),
+            (499, 100, None),
+            // skipped milestones only announce the highest one
+            (1200, 100, Some(1000)),
+            // unstarring below an announced milestone does not re-arm it
+            (99, 100, None),
+            (100, 500, None),
+        ];
+
+        for (stars, last_milestone, expected) in cases {
+            assert_eq!(
+                repository_stars(stars, last_milestone).reached_milestone(&milestones),
+                expected,
+                "{stars} stars after {last_milestone}"
+            );
+        }
+
+        assert_eq!(repository_stars(1000, 0).reached_milestone(&[]), None);
+        // milestones do not have to be sorted
+        assert_eq!(
+            repository_stars(600, 0).reached_milestone(&[1000, 500, 100]),
+            Some(500)
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use futures::TryStreamExt;
+use serenity::all::CreateAutocompleteResponse;
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+#[derive(Debug)]
+pub struct GithubRoute {
+    pub id: i64,
+
+    pub repository: String,
+    pub event: Option<String>,
+    pub branch: Option<String>,
+
+    pub channel_id: i64,
+}
+
+impl FromRow<'_, SqliteRow> for GithubRoute {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            repository: row.try_get("repository")?,
+            event: row.try_get("event")?,
+            branch: row.try_get("branch")?,
+            channel_id: row.try_get("channel_id")?,
+        })
+    }
+}
+
+impl GithubRoute {
+    pub fn matches(&self, repository: &str, event: &str, branch: Option<&str>) -> bool {
+        if !crate::utils::glob_matches(&self.repository, repository) {
+            return false;
+        }
+
+        if let Some(route_event) = &self.event
+            && !route_event.eq_ignore_ascii_case(event)
+        {
+            return false;
+        }
+
+        match (&self.branch, branch) {
+            (Some(route_branch), Some(branch)) => crate::utils::glob_matches(route_branch, branch),
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+
+    /// Routes restricting more dimensions win over broader ones
+    #[inline]
+    fn specificity(&self) -> u8 {
+        self.event.is_some() as u8 * 2 + self.branch.is_some() as u8
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "`{}` {} {} → <#{}>",
+            self.repository,
+            self.event
+                .as_ref()
+                .map_or_else(|| "any event".to_string(), |e| format!("`{e}`")),
+            self.branch
+                .as_ref()
+                .map_or_else(|| "any branch".to_string(), |b| format!("on `{b}`")),
+            self.channel_id
+        )
+    }
+}
+
+pub async fn all_github_routes(pool: &sqlx::SqlitePool) -> Result<Vec<GithubRoute>, anyhow::Error> {
+    let routes = sqlx::query_as::<_, GithubRoute>("SELECT * FROM github_routes ORDER BY id ASC")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(routes)
+}
+
+/// Picks the channel of the most specific matching route, or `fallback` if none match.
+/// `repository` is the repository's full name, or the organization login for organization events.
+pub async fn resolve_github_channel(
+    pool: &sqlx::SqlitePool,
+    fallback: u64,
+    repository: &str,
+    event: &str,
+    branch: Option<&str>,
+) -> Result<u64, anyhow::Error> {
+    let routes = all_github_routes(pool).await?;
+
+    Ok(
+        most_specific_github_route(&routes, repository, event, branch)
+            .map_or(fallback, |route| route.channel_id as u64),
+    )
+}
+
+/// The most specific of the matching routes, the oldest one wins ties
+pub fn most_specific_github_route<'a>(
+    routes: &'a [GithubRoute],
+    repository: &str,
+    event: &str,
+    branch: Option<&str>,
+) -> Option<&'a GithubRoute> {
+    routes
+        .iter()
+        .filter(|route| route.matches(repository, event, branch))
+        .max_by_key(|route| (route.specificity(), std::cmp::Reverse(route.id)))
+}
+
+pub async fn autocomplete_github_route_id<'a>(
+    ctx: crate::BotContext<'_>,
+    partial: &'a str,
+) -> serenity::all::CreateAutocompleteResponse<'a> {
+    let database = &ctx.data().database;
+    let mut routes = sqlx::query_as(
+        "SELECT * FROM github_routes WHERE repository LIKE ? ORDER BY created DESC LIMIT 25",
+    )
+    .bind(format!("%{}%", partial))
+    .fetch(database.read());
+
+    let mut response = CreateAutocompleteResponse::new();
+
+    while let Ok(Some(route)) = routes.try_next().await {
+        let route: GithubRoute = route;
+        response = response.add_choice(serenity::all::AutocompleteChoice::new(
+            format!(
+                "#{} {} ({}, {})",
+                route.id,
+                route.repository,
+                route.event.as_deref().unwrap_or("any event"),
+                route.branch.as_deref().unwrap_or("any branch")
+            ),
+            route.id as u64,
+        ));
+    }
+
+    response
+}
+
+/// Event names as sent in GitHub's `X-GitHub-Event` header
+pub const GITHUB_EVENT_NAMES: &[&str] = &[
+    "push",
+    "pull_request",
+    "pull_request_review",
+    "pull_request_review_comment",
+    "issue_comment",
+    "issues",
+    "discussion",
+    "discussion_comment",
+    "star",
+    "release",
+    "create",
+    "delete",
+    "deployment",
+    "deployment_status",
+    "workflow_run",
+    "workflow_job",
+    "check_run",
+    "check_suite",
+    "status",
+    "organization",
+    "membership",
+    "team",
+    "repository",
+    "fork",
+    "sponsorship",
+];
+
+pub async fn autocomplete_github_event_name<'a>(
+    _ctx: crate::BotContext<'_>,
+    partial: &'a str,
+) -> serenity::all::CreateAutocompleteResponse<'a> {
+    let mut response = CreateAutocompleteResponse::new();
+
+    for event in GITHUB_EVENT_NAMES
+        .iter()
+        .filter(|event| event.contains(partial))
+        .take(25)
+    {
+        response = response.add_choice(serenity::all::AutocompleteChoice::new(*event, *event));
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GithubRoute;
+
+    fn route(id: i64, repository: &str, event: Option<&str>, branch: Option<&str>) -> GithubRoute {
+        GithubRoute {
+            id,
+            repository: repository.to_string(),
+            event: event.map(str::to_string),
+            branch: branch.map(str::to_string),
+            channel_id: id * 100,
+        }
+    }
+
+    #[test]
+    fn matches() {
+        let cases = [
+            (
+                route(1, "*", None, None),
+                "calagopus/panel",
+                "push",
+                None,
+                true,
+            ),
+            (
+                route(1, "calagopus/*", None, None),
+                "calagopus/panel",
+                "push",
+                Some("main"),
+                true,
+            ),
+            (
+                route(1, "calagopus/*", None, None),
+                "other/panel",
+                "push",
+                Some("main"),
+                false,
+            ),
+            (
+                route(1, "Calagopus/Panel", None, None),
+                "calagopus/panel",
+                "push",
+                None,
+                true,
+            ),
+            (
+                route(1, "*", Some("push"), None),
+                "calagopus/panel",
+                "PUSH",
+                None,
+                true,
+            ),
+            (
+                route(1, "*", Some("push"), None),
+                "calagopus/panel",
+                "pull_request",
+                None,
+                false,
+            ),
+            (
+                route(1, "*", None, Some("release/*")),
+                "calagopus/panel",
+                "push",
+                Some("release/1.0"),
+                true,
+            ),
+            (
+                route(1, "*", None, Some("release/*")),
+                "calagopus/panel",
+                "push",
+                Some("main"),
+                false,
+            ),
+            // a branch route never matches events without a branch
+            (
+                route(1, "*", None, Some("*")),
+                "calagopus/panel",
+                "star",
+                None,
+                false,
+            ),
+            (
+                route(1, "*", Some("push"), Some("main")),
+                "calagopus/panel",
+                "push",
+                Some("main"),
+                true,
+            ),
+            (
+                route(1, "*", Some("push"), Some("main")),
+                "calagopus/panel",
+                "create",
+                Some("main"),
+                false,
+            ),
+        ];
+
+        for (route, repository, event, branch, expected) in cases {
+            assert_eq!(
+                route.matches(repository, event, branch),
+                expected,
+                "{} matching {repository} {event} {branch:?}",
+                route.describe()
+            );
+        }
+    }
+
+    #[test]
+    fn specificity() {
+        let cases = [
+            (route(1, "*", None, None), 0),
+            (route(1, "*", None, Some("main")), 1),
+            (route(1, "*", Some("push"), None), 2),
+            (route(1, "*", Some("push"), Some("main")), 3),
+            // repository globs do not count, only the dimensions a route restricts
+            (route(1, "calagopus/panel", None, None), 0),
+        ];
+
+        for (route, expected) in cases {
+            assert_eq!(route.specificity(), expected, "{}", route.describe());
+        }
+    }
+
+    #[test]
+    fn most_specific_github_route() {
+        let routes = [
+            route(1, "*", None, None),
+            route(2, "calagopus/*", None, Some("main")),
+            route(3, "calagopus/*", Some("push"), None),
+            route(4, "calagopus/*", Some("push"), Some("main")),
+            route(5, "calagopus/panel", Some("push"), None),
+            route(6, "calagopus/*", Some("star"), None),
+            route(7, "calagopus/*", Some("star"), None),
+        ];
+
+        let cases = [
+            ("other/repo", "push", Some("main"), Some(1)),
+            ("calagopus/panel", "push", Some("main"), Some(4)),
+            // an event route beats a branch route
+            ("calagopus/panel", "push", Some("dev"), Some(3)),
+            ("calagopus/panel", "create", Some("main"), Some(2)),
+            ("calagopus/panel", "release", None, Some(1)),
+            // ties go to the oldest route
+            ("calagopus/wings", "star", None, Some(6)),
+        ];
+
+        for (repository, event, branch, expected) in cases {
+            assert_eq!(
+                super::most_specific_github_route(&routes, repository, event, branch)
+                    .map(|route| route.id),
+                expected,
+                "{repository} {event} {branch:?}"
+            );
+        }
+
+        assert!(super::most_specific_github_route(&[], "calagopus/panel", "push", None).is_none());
+    }
+}
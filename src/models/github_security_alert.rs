@@ -0,0 +1,275 @@
+use futures::TryStreamExt;
+use serenity::all::CreateAutocompleteResponse;
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+#[derive(Debug)]
+pub struct GithubSecurityAlert {
+    pub id: i64,
+    /// The webhook event the alert came from, e.g. `dependabot_alert`
+    pub kind: String,
+    /// Empty for global advisories
+    pub repository: String,
+    /// Alert number, or the GHSA ID for advisories
+    pub key: String,
+    pub channel_id: i64,
+    pub message_id: Option<i64>,
+
+    pub state: String,
+    pub severity: Option<String>,
+    pub summary: String,
+    pub package: Option<String>,
+    pub vulnerable_range: Option<String>,
+    pub url: String,
+
+    pub acknowledged_by: Option<i64>,
+    pub acknowledged: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl FromRow<'_, SqliteRow> for GithubSecurityAlert {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            kind: row.try_get("kind")?,
+            repository: row.try_get("repository")?,
+            key: row.try_get("key")?,
+            channel_id: row.try_get("channel_id")?,
+            message_id: row.try_get("message_id")?,
+            state: row.try_get("state")?,
+            severity: row.try_get("severity")?,
+            summary: row.try_get("summary")?,
+            package: row.try_get("package")?,
+            vulnerable_range: row.try_get("vulnerable_range")?,
+            url: row.try_get("url")?,
+            acknowledged_by: row.try_get("acknowledged_by")?,
+            acknowledged: row
+                .try_get::<Option<i64>, _>("acknowledged")?
+                .and_then(|a| chrono::DateTime::<chrono::Utc>::from_timestamp(a, 0)),
+        })
+    }
+}
+
+impl GithubSecurityAlert {
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        matches!(self.state.as_str(), "open" | "published" | "updated")
+    }
+
+    fn kind_label(&self) -> &str {
+        match self.kind.as_str() {
+            "dependabot_alert" => "Dependabot",
+            "repository_vulnerability_alert" => "Vulnerability",
+            "secret_scanning_alert" => "Secret scanning",
+            "code_scanning_alert" => "Code scanning",
+            "security_advisory" => "Security advisory",
+            kind => kind,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        if self.repository.is_empty() {
+            format!("{} {}", self.kind_label(), self.key)
+        } else {
+            format!("{} #{} in {}", self.kind_label(), self.key, self.repository)
+        }
+    }
+
+    pub async fn send_or_update(
+        &mut self,
+        http: &serenity::http::Http,
+        database: &crate::database::Database,
+    ) -> Result<(), anyhow::Error> {
+        let channel_id = serenity::all::GenericChannelId::new(self.channel_id as u64);
+
+        if let Some(message_id) = self.message_id {
+            channel_id
+                .edit_message(
+                    http,
+                    (message_id as u64).into(),
+                    serenity::all::EditMessage::new()
+                        .components(&[self.get_component()])
+                        .flags(serenity::all::MessageFlags::IS_COMPONENTS_V2),
+                )
+                .await?;
+        } else {
+            let message = channel_id
+                .send_message(
+                    http,
+                    serenity::all::CreateMessage::new()
+                        .components(&[self.get_component()])
+                        .flags(serenity::all::MessageFlags::IS_COMPONENTS_V2),
+                )
+                .await?;
+
+            sqlx::query("UPDATE github_security_alerts SET message_id = ? WHERE id = ?")
+                .bind(message.id.get() as i64)
+                .bind(self.id)
+                .execute(database.write())
+                .await?;
+            self.message_id = Some(message.id.get() as i64);
+        }
+
+        Ok(())
+    }
+
+    pub fn get_component(&self) -> serenity::all::CreateComponent<'_> {
+        let mut details = format!(
+            "**{}**\n**State**: `{}`",
+            crate::utils::neutralize_mentions(&self.summary),
+            self.state
+        );
+        if let Some(severity) = &self.severity {
+            details.push_str(&format!("\n**Severity**: `{severity}`"));
+        }
+        if let Some(package) = &self.package {
+            details.push_str(&format!("\n**Package**: `{package}`"));
+        }
+        if let Some(vulnerable_range) = &self.vulnerable_range {
+            details.push_str(&format!("\n**Affected versions**: `{vulnerable_range}`"));
+        }
+
+        let mut container_components = vec![
+            serenity::all::CreateContainerComponent::Section(serenity::all::CreateSection::new(
+                vec![
+                    serenity::all::CreateSectionComponent::TextDisplay(
+                        serenity::all::CreateTextDisplay::new(format!(
+                            "## {} {} alert {}",
+                            if self.is_open() {
+                                "<:deny:1156939743230173234>"
+                            } else {
+                                "<:accept:1156939740654878750>"
+                            },
+                            self.kind_label(),
+                            if self.repository.is_empty() {
+                                format!("`{}`", self.key)
+                            } else {
+                                format!("#{} in `{}`", self.key, self.repository)
+                            }
+                        )),
+                    ),
+                    serenity::all::CreateSectionComponent::TextDisplay(
+                        serenity::all::CreateTextDisplay::new(details),
+                    ),
+                ],
+                serenity::all::CreateSectionAccessory::Button(
+                    serenity::all::CreateButton::new_link(self.url.clone()).label("View Alert"),
+                ),
+            )),
+            serenity::all::CreateContainerComponent::Separator(
+                serenity::all::CreateSeparator::new(),
+            ),
+        ];
+
+        container_components.push(serenity::all::CreateContainerComponent::TextDisplay(
+            serenity::all::CreateTextDisplay::new(
+                match (self.acknowledged_by, self.acknowledged) {
+                    (Some(user_id), Some(acknowledged)) => format!(
+                        "-# Acknowledged by <@{user_id}> <t:{}:R>",
+                        acknowledged.timestamp()
+                    ),
+                    _ if self.is_open() => format!(
+                        "-# Not acknowledged yet, use `/security acknowledge` to take it. (`#{}`)",
+                        self.id
+                    ),
+                    _ => "-# Resolved".to_string(),
+                },
+            ),
+        ));
+
+        serenity::all::CreateComponent::Container(serenity::all::CreateContainer::new(
+            container_components,
+        ))
+    }
+}
+
+pub async fn get_github_security_alert(
+    pool: &sqlx::SqlitePool,
+    kind: &str,
+    repository: &str,
+    key: &str,
+) -> Result<Option<GithubSecurityAlert>, anyhow::Error> {
+    let alert = sqlx::query_as::<_, GithubSecurityAlert>(
+        "SELECT * FROM github_security_alerts WHERE kind = ? AND repository = ? AND key = ?",
+    )
+    .bind(kind)
+    .bind(repository)
+    .bind(key)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(alert)
+}
+
+/// Creates an empty row, the caller is expected to fill it in and call [`update_github_security_alert`]
+pub async fn create_github_security_alert(
+    pool: &sqlx::SqlitePool,
+    kind: &str,
+    repository: &str,
+    key: &str,
+    channel_id: u64,
+) -> Result<GithubSecurityAlert, anyhow::Error> {
+    let alert = sqlx::query_as::<_, GithubSecurityAlert>(
+        "INSERT INTO github_security_alerts (kind, repository, key, channel_id, state, summary, url)
+         VALUES (?, ?, ?, ?, '', '', '')
+         RETURNING *",
+    )
+    .bind(kind)
+    .bind(repository)
+    .bind(key)
+    .bind(channel_id as i64)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(alert)
+}
+
+pub async fn update_github_security_alert(
+    pool: &sqlx::SqlitePool,
+    alert: &GithubSecurityAlert,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE github_security_alerts SET state = ?, severity = ?, summary = ?, package = ?, vulnerable_range = ?, url = ?, acknowledged_by = ?, acknowledged = ? WHERE id = ?",
+    )
+    .bind(&alert.state)
+    .bind(&alert.severity)
+    .bind(&alert.summary)
+    .bind(&alert.package)
+    .bind(&alert.vulnerable_range)
+    .bind(&alert.url)
+    .bind(alert.acknowledged_by)
+    .bind(alert.acknowledged.map(|acknowledged| acknowledged.timestamp()))
+    .bind(alert.id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn autocomplete_github_security_alert_id<'a>(
+    ctx: crate::BotContext<'_>,
+    partial: &'a str,
+) -> serenity::all::CreateAutocompleteResponse<'a> {
+    // alerts must not leak outside the restricted channel, not even their titles
+    if ctx.data().env.github_security_channel_id != Some(ctx.channel_id().get()) {
+        return CreateAutocompleteResponse::new();
+    }
+
+    let database = &ctx.data().database;
+    let mut alerts = sqlx::query_as(
+        "SELECT * FROM github_security_alerts WHERE acknowledged_by IS NULL AND (repository LIKE ? OR summary LIKE ?) ORDER BY created DESC LIMIT 25",
+    )
+    .bind(format!("%{}%", partial))
+    .bind(format!("%{}%", partial))
+    .fetch(database.read());
+
+    let mut response = CreateAutocompleteResponse::new();
+
+    while let Ok(Some(alert)) = alerts.try_next().await {
+        let alert: GithubSecurityAlert = alert;
+        response = response.add_choice(serenity::all::AutocompleteChoice::new(
+            crate::utils::truncate_text(&format!("#{} {}", alert.id, alert.describe()), 100),
+            alert.id as u64,
+        ));
+    }
+
+    response
+}
@@ -0,0 +1,243 @@
+use futures::TryStreamExt;
+use serenity::all::CreateAutocompleteResponse;
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+#[derive(Debug)]
+pub struct GithubWebhookEvent {
+    pub id: i64,
+    pub delivery_id: Option<String>,
+    /// The `GITHUB_SOURCES` entry the delivery came in through, `None` for the default webhook
+    pub source: Option<String>,
+
+    pub event: String,
+    pub payload: String,
+
+    pub attempts: i64,
+    pub next_attempt: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_error: Option<String>,
+    pub processed: Option<chrono::DateTime<chrono::Utc>>,
+
+    pub created: chrono::DateTime<chrono::Utc>,
+
+    /// Not stored, set when replaying a delivery by hand
+    pub replayed: bool,
+    /// Not stored, set when replaying a delivery into another channel instead of its routed one.
+    /// Such a replay is a dry run, it only sends fresh messages and never writes to the database
+    pub replay_channel_id: Option<u64>,
+}
+
+impl FromRow<'_, SqliteRow> for GithubWebhookEvent {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            delivery_id: row.try_get("delivery_id")?,
+            source: row.try_get("source")?,
+            event: row.try_get("event")?,
+            payload: row.try_get("payload")?,
+            attempts: row.try_get("attempts")?,
+            next_attempt: row
+                .try_get::<Option<i64>, _>("next_attempt")?
+                .and_then(|n| chrono::DateTime::<chrono::Utc>::from_timestamp(n, 0)),
+            last_error: row.try_get("last_error")?,
+            processed: row
+                .try_get::<Option<i64>, _>("processed")?
+                .and_then(|p| chrono::DateTime::<chrono::Utc>::from_timestamp(p, 0)),
+            created: chrono::DateTime::<chrono::Utc>::from_timestamp(row.try_get("created")?, 0)
+                .unwrap_or_default(),
+            replayed: false,
+            replay_channel_id: None,
+        })
+    }
+}
+
+/// Returns `None` if the delivery was already processed or is still queued.
+/// Deliveries that were given up on are queued again from scratch.
+pub async fn insert_github_webhook_event(
+    pool: &sqlx::SqlitePool,
+    delivery_id: Option<&str>,
+    source: Option<&str>,
+    event: &str,
+    payload: &str,
+) -> Result<Option<i64>, anyhow::Error> {
+    let id: Option<i64> = sqlx::query_scalar(
+        "INSERT INTO github_webhook_events (delivery_id, source, event, payload, next_attempt) VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT (delivery_id) DO UPDATE SET source = excluded.source, payload = excluded.payload, attempts = 0, next_attempt = excluded.next_attempt, last_error = NULL
+         WHERE github_webhook_events.processed IS NULL AND github_webhook_events.next_attempt IS NULL
+         RETURNING id",
+    )
+    .bind(delivery_id)
+    .bind(source)
+    .bind(event)
+    .bind(payload)
+    .bind(chrono::Utc::now().timestamp())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn get_github_webhook_event(
+    pool: &sqlx::SqlitePool,
+    delivery_id: &str,
+) -> Result<Option<GithubWebhookEvent>, anyhow::Error> {
+    let event = sqlx::query_as::<_, GithubWebhookEvent>(
+        "SELECT * FROM github_webhook_events WHERE delivery_id = ?",
+    )
+    .bind(delivery_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(event)
+}
+
+/// Oldest first, so events are handled in the order GitHub delivered them
+pub async fn due_github_webhook_events(
+    pool: &sqlx::SqlitePool,
+    now: chrono::DateTime<chrono::Utc>,
+    limit: i64,
+) -> Result<Vec<GithubWebhookEvent>, anyhow::Error> {
+    let events = sqlx::query_as::<_, GithubWebhookEvent>(
+        "SELECT * FROM github_webhook_events WHERE next_attempt IS NOT NULL AND next_attempt <= ? ORDER BY id ASC LIMIT ?",
+    )
+    .bind(now.timestamp())
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+pub async fn next_github_webhook_event_attempt(
+    pool: &sqlx::SqlitePool,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, anyhow::Error> {
+    let next_attempt: Option<i64> =
+        sqlx::query_scalar("SELECT MIN(next_attempt) FROM github_webhook_events")
+            .fetch_one(pool)
+            .await?;
+
+    Ok(next_attempt.and_then(|n| chrono::DateTime::<chrono::Utc>::from_timestamp(n, 0)))
+}
+
+pub async fn set_github_webhook_event_processed(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    attempts: i64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE github_webhook_events SET attempts = ?, next_attempt = NULL, last_error = NULL, processed = ? WHERE id = ?",
+    )
+    .bind(attempts)
+    .bind(chrono::Utc::now().timestamp())
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// A `next_attempt` of `None` parks the event permanently, keeping it around for inspection
+pub async fn reschedule_github_webhook_event(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    attempts: i64,
+    next_attempt: Option<chrono::DateTime<chrono::Utc>>,
+    last_error: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE github_webhook_events SET attempts = ?, next_attempt = ?, last_error = ? WHERE id = ?",
+    )
+    .bind(attempts)
+    .bind(next_attempt.map(|n| n.timestamp()))
+    .bind(last_error)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Forgets finished (processed or given up) deliveries older than `before`
+pub async fn prune_github_webhook_events(
+    pool: &sqlx::SqlitePool,
+    before: chrono::DateTime<chrono::Utc>,
+) -> Result<u64, anyhow::Error> {
+    let result =
+        sqlx::query("DELETE FROM github_webhook_events WHERE next_attempt IS NULL AND created < ?")
+            .bind(before.timestamp())
+            .execute(pool)
+            .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Parks a CI event until a message for its commit exists, see [`requeue_deferred_github_webhook_events`]
+pub async fn defer_github_webhook_event(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    repository_id: i64,
+    sha: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE github_webhook_events SET deferred_repository_id = ?, deferred_sha = ? WHERE id = ?",
+    )
+    .bind(repository_id)
+    .bind(sha)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Queues every event deferred for a commit again, returning how many there were
+pub async fn requeue_deferred_github_webhook_events(
+    pool: &sqlx::SqlitePool,
+    repository_id: i64,
+    sha: &str,
+) -> Result<u64, anyhow::Error> {
+    let result = sqlx::query(
+        "UPDATE github_webhook_events SET deferred_repository_id = NULL, deferred_sha = NULL, processed = NULL, attempts = 0, next_attempt = ?
+         WHERE deferred_repository_id = ? AND deferred_sha = ?",
+    )
+    .bind(chrono::Utc::now().timestamp())
+    .bind(repository_id)
+    .bind(sha)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn autocomplete_github_webhook_delivery<'a>(
+    ctx: crate::BotContext<'_>,
+    partial: &'a str,
+) -> serenity::all::CreateAutocompleteResponse<'a> {
+    let database = &ctx.data().database;
+    let mut events = sqlx::query_as(
+        "SELECT * FROM github_webhook_events WHERE delivery_id IS NOT NULL AND (delivery_id LIKE ? OR event LIKE ?) ORDER BY id DESC LIMIT 25",
+    )
+    .bind(format!("%{}%", partial))
+    .bind(format!("%{}%", partial))
+    .fetch(database.read());
+
+    let mut response = CreateAutocompleteResponse::new();
+
+    while let Ok(Some(event)) = events.try_next().await {
+        let event: GithubWebhookEvent = event;
+        let Some(delivery_id) = event.delivery_id else {
+            continue;
+        };
+
+        response = response.add_choice(serenity::all::AutocompleteChoice::new(
+            format!(
+                "{} {} ({})",
+                event.event,
+                delivery_id,
+                event.created.format("%Y-%m-%d %H:%M")
+            ),
+            delivery_id,
+        ));
+    }
+
+    response
+}
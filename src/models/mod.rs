@@ -1,5 +1,27 @@
+mod github_discussion;
+pub use github_discussion::*;
+mod github_issue;
+pub use github_issue::*;
 mod github_message;
 pub use github_message::*;
+mod github_pull_request;
+pub use github_pull_request::*;
+mod github_push_filter;
+pub use github_push_filter::*;
+mod github_repository_stars;
+pub use github_repository_stars::*;
+mod github_route;
+pub use github_route::*;
+mod github_security_alert;
+pub use github_security_alert::*;
+mod github_webhook_event;
+pub use github_webhook_event::*;
+mod sponsor_link;
+pub use sponsor_link::*;
+mod sponsor_role;
+pub use sponsor_role::*;
+mod sponsors_activity;
+pub use sponsors_activity::*;
 mod text_message;
 pub use text_message::*;
 mod sent_sponsorship;
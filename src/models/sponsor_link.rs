@@ -0,0 +1,118 @@
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+#[derive(Debug)]
+pub struct SponsorLink {
+    pub id: i64,
+    pub discord_id: i64,
+    /// `None` until the code was found on the GitHub profile
+    pub github_id: Option<i64>,
+    pub github_login: String,
+    pub code: String,
+    pub verified: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl FromRow<'_, SqliteRow> for SponsorLink {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            discord_id: row.try_get("discord_id")?,
+            github_id: row.try_get("github_id")?,
+            github_login: row.try_get("github_login")?,
+            code: row.try_get("code")?,
+            verified: row
+                .try_get::<Option<i64>, _>("verified")?
+                .and_then(|v| chrono::DateTime::<chrono::Utc>::from_timestamp(v, 0)),
+        })
+    }
+}
+
+pub async fn get_sponsor_link(
+    pool: &sqlx::SqlitePool,
+    discord_id: u64,
+) -> Result<Option<SponsorLink>, anyhow::Error> {
+    let link = sqlx::query_as::<_, SponsorLink>("SELECT * FROM sponsor_links WHERE discord_id = ?")
+        .bind(discord_id as i64)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(link)
+}
+
+pub async fn get_sponsor_link_by_github_id(
+    pool: &sqlx::SqlitePool,
+    github_id: i64,
+) -> Result<Option<SponsorLink>, anyhow::Error> {
+    let link = sqlx::query_as::<_, SponsorLink>("SELECT * FROM sponsor_links WHERE github_id = ?")
+        .bind(github_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(link)
+}
+
+/// Starts linking over, dropping any previous link of the user.
+/// The caller removes the roles of a previously verified link.
+pub async fn create_pending_sponsor_link(
+    pool: &sqlx::SqlitePool,
+    discord_id: u64,
+    github_login: &str,
+    code: &str,
+) -> Result<SponsorLink, anyhow::Error> {
+    let link = sqlx::query_as::<_, SponsorLink>(
+        "INSERT INTO sponsor_links (discord_id, github_login, code) VALUES (?, ?, ?)
+         ON CONFLICT (discord_id) DO UPDATE SET github_id = NULL, github_login = excluded.github_login, code = excluded.code, verified = NULL
+         RETURNING *",
+    )
+    .bind(discord_id as i64)
+    .bind(github_login)
+    .bind(code)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(link)
+}
+
+pub async fn verify_sponsor_link(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    github_id: i64,
+    github_login: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE sponsor_links SET github_id = ?, github_login = ?, verified = ? WHERE id = ?",
+    )
+    .bind(github_id)
+    .bind(github_login)
+    .bind(chrono::Utc::now().timestamp())
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_sponsor_link(
+    pool: &sqlx::SqlitePool,
+    discord_id: u64,
+) -> Result<Option<SponsorLink>, anyhow::Error> {
+    let link = sqlx::query_as::<_, SponsorLink>(
+        "DELETE FROM sponsor_links WHERE discord_id = ? RETURNING *",
+    )
+    .bind(discord_id as i64)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(link)
+}
+
+pub async fn verified_sponsor_links(
+    pool: &sqlx::SqlitePool,
+) -> Result<Vec<SponsorLink>, anyhow::Error> {
+    let links = sqlx::query_as::<_, SponsorLink>(
+        "SELECT * FROM sponsor_links WHERE github_id IS NOT NULL ORDER BY id ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(links)
+}
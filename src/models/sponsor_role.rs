@@ -0,0 +1,157 @@
+use sqlx::{FromRow, Row, sqlite::SqliteRow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SponsorRoleRequirement {
+    /// An active monthly sponsorship of at least this much
+    Monthly(i64),
+    /// At least this much sponsored in total, one-time and monthly
+    Lifetime(i64),
+    /// Sponsored monthly before but no longer does, after the grace period
+    Former,
+}
+
+impl SponsorRoleRequirement {
+    #[inline]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Monthly(_) => "monthly",
+            Self::Lifetime(_) => "lifetime",
+            Self::Former => "former",
+        }
+    }
+
+    #[inline]
+    pub fn amount_in_cents(&self) -> i64 {
+        match self {
+            Self::Monthly(cents) | Self::Lifetime(cents) => *cents,
+            Self::Former => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SponsorRole {
+    pub id: i64,
+    pub role_id: u64,
+    pub requirement: SponsorRoleRequirement,
+}
+
+impl FromRow<'_, SqliteRow> for SponsorRole {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        let amount_in_cents: i64 = row.try_get("amount_in_cents")?;
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            role_id: row.try_get::<i64, _>("role_id")? as u64,
+            requirement: match row.try_get::<String, _>("kind")?.as_str() {
+                "monthly" => SponsorRoleRequirement::Monthly(amount_in_cents),
+                "lifetime" => SponsorRoleRequirement::Lifetime(amount_in_cents),
+                "former" => SponsorRoleRequirement::Former,
+                kind => {
+                    return Err(sqlx::Error::ColumnDecode {
+                        index: "kind".into(),
+                        source: format!("unknown sponsor role kind `{kind}`").into(),
+                    });
+                }
+            },
+        })
+    }
+}
+
+impl SponsorRole {
+    /// Monthly roles are kept for `grace` after a sponsorship ends, so a late renewal does not flap them
+    pub fn qualifies(
+        &self,
+        sponsor: &crate::sponsors::EvaluatedSponsor,
+        now: chrono::DateTime<chrono::Utc>,
+        grace: chrono::Duration,
+    ) -> bool {
+        match self.requirement {
+            SponsorRoleRequirement::Monthly(cents) => {
+                if sponsor.active {
+                    sponsor.monthly_in_cents >= cents
+                } else {
+                    sponsor
+                        .ended_at
+                        .is_some_and(|ended_at| ended_at + grace > now)
+                        && sponsor.last_monthly_in_cents >= cents
+                }
+            }
+            SponsorRoleRequirement::Lifetime(cents) => sponsor.lifetime_in_cents >= cents,
+            // only once the monthly roles are gone, so nobody holds both at the same time
+            SponsorRoleRequirement::Former => {
+                !sponsor.active
+                    && sponsor.recurring_spells > 0
+                    && sponsor
+                        .ended_at
+                        .is_none_or(|ended_at| ended_at + grace <= now)
+            }
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.requirement {
+            SponsorRoleRequirement::Monthly(cents) => format!(
+                "<@&{}> for `${:.2}`+ monthly",
+                self.role_id,
+                cents as f64 / 100.0
+            ),
+            SponsorRoleRequirement::Lifetime(cents) => format!(
+                "<@&{}> for `${:.2}`+ in total",
+                self.role_id,
+                cents as f64 / 100.0
+            ),
+            SponsorRoleRequirement::Former => {
+                format!("<@&{}> for former monthly sponsors", self.role_id)
+            }
+        }
+    }
+}
+
+pub async fn all_sponsor_roles(pool: &sqlx::SqlitePool) -> Result<Vec<SponsorRole>, anyhow::Error> {
+    let roles = sqlx::query_as::<_, SponsorRole>(
+        "SELECT * FROM sponsor_roles ORDER BY kind ASC, amount_in_cents ASC, id ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(roles)
+}
+
+/// A role has at most one rule per kind, adding it again changes its amount
+pub async fn upsert_sponsor_role(
+    pool: &sqlx::SqlitePool,
+    role_id: u64,
+    requirement: SponsorRoleRequirement,
+) -> Result<SponsorRole, anyhow::Error> {
+    let role = sqlx::query_as::<_, SponsorRole>(
+        "INSERT INTO sponsor_roles (role_id, kind, amount_in_cents) VALUES (?, ?, ?)
+         ON CONFLICT (role_id, kind) DO UPDATE SET amount_in_cents = excluded.amount_in_cents
+         RETURNING *",
+    )
+    .bind(role_id as i64)
+    .bind(requirement.kind())
+    .bind(requirement.amount_in_cents())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(role)
+}
+
+/// Removes the rules of a role, only the one of `kind` if given
+pub async fn delete_sponsor_roles(
+    pool: &sqlx::SqlitePool,
+    role_id: u64,
+    kind: Option<&str>,
+) -> Result<Vec<SponsorRole>, anyhow::Error> {
+    let roles = sqlx::query_as::<_, SponsorRole>(
+        "DELETE FROM sponsor_roles WHERE role_id = ? AND (? IS NULL OR kind = ?) RETURNING *",
+    )
+    .bind(role_id as i64)
+    .bind(kind)
+    .bind(kind)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(roles)
+}
@@ -0,0 +1,85 @@
+use crate::sponsors::GithubSponsorshipNode;
+
+/// Newest activity timestamp in the ledger, `None` if nothing was stored yet
+pub async fn latest_sponsors_activity_timestamp(
+    pool: &sqlx::SqlitePool,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, anyhow::Error> {
+    let timestamp: Option<i64> =
+        sqlx::query_scalar("SELECT MAX(timestamp) FROM sponsors_activities")
+            .fetch_one(pool)
+            .await?;
+
+    Ok(timestamp.and_then(|t| chrono::DateTime::<chrono::Utc>::from_timestamp(t, 0)))
+}
+
+/// Activities that are already stored are overwritten, their privacy level can change
+pub async fn upsert_sponsors_activities(
+    pool: &sqlx::SqlitePool,
+    activities: &[GithubSponsorshipNode],
+) -> Result<(), anyhow::Error> {
+    let mut transaction = pool.begin().await?;
+    insert_sponsors_activities(&mut transaction, activities).await?;
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Replaces the whole ledger with a full fetch, dropping activities GitHub no longer returns
+pub async fn replace_sponsors_activities(
+    pool: &sqlx::SqlitePool,
+    activities: &[GithubSponsorshipNode],
+) -> Result<(), anyhow::Error> {
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query("DELETE FROM sponsors_activities")
+        .execute(&mut *transaction)
+        .await?;
+    insert_sponsors_activities(&mut transaction, activities).await?;
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+async fn insert_sponsors_activities(
+    transaction: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    activities: &[GithubSponsorshipNode],
+) -> Result<(), anyhow::Error> {
+    for activity in activities {
+        let action = activity
+            .action
+            .and_then(|action| serde_json::to_value(action).ok())
+            .and_then(|action| action.as_str().map(str::to_string));
+
+        sqlx::query(
+            "INSERT INTO sponsors_activities (id, action, timestamp, data) VALUES (?, ?, ?, ?)
+             ON CONFLICT (id) DO UPDATE SET action = excluded.action, timestamp = excluded.timestamp, data = excluded.data",
+        )
+        .bind(&activity.id)
+        .bind(action)
+        .bind(activity.timestamp.map(|timestamp| timestamp.timestamp()))
+        .bind(serde_json::to_string(activity)?)
+        .execute(&mut **transaction)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Old to new, in the order GitHub returned activities sharing a timestamp
+pub async fn all_sponsors_activities(
+    pool: &sqlx::SqlitePool,
+) -> Result<Vec<GithubSponsorshipNode>, anyhow::Error> {
+    let rows: Vec<String> = sqlx::query_scalar(
+        "SELECT data FROM sponsors_activities ORDER BY timestamp ASC, rowid ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let activities = rows
+        .iter()
+        .map(|data| serde_json::from_str(data))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(activities)
+}
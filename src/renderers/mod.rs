@@ -0,0 +1,292 @@
+mod organization;
+mod reference;
+mod release;
+mod repository;
+
+use octocrab::models::webhook_events::{WebhookEvent, WebhookEventType};
+use serde::Serialize;
+use serenity::all::{
+    CreateButton, CreateComponent, CreateContainer, CreateContainerComponent, CreateSection,
+    CreateSectionAccessory, CreateSectionComponent, CreateSeparator, CreateTextDisplay,
+    CreateThumbnail, CreateUnfurledMediaItem,
+};
+
+/// Where a rendered message is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderTarget {
+    /// The channel the event's routes resolve to
+    Routed,
+    /// `GITHUB_ADMIN_CHANNEL_ID`, routed like any other event if it is not set
+    Admin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RenderedAccessory {
+    Thumbnail { url: String },
+    Link { label: String, url: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RenderedBlock {
+    Text {
+        content: String,
+    },
+    Section {
+        texts: Vec<String>,
+        accessory: RenderedAccessory,
+    },
+    Separator,
+}
+
+/// A message as plain data, turned into Discord components only when it is sent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenderedMessage {
+    pub target: RenderTarget,
+    pub blocks: Vec<RenderedBlock>,
+}
+
+impl RenderedMessage {
+    pub fn new(target: RenderTarget) -> Self {
+        Self {
+            target,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Title and description next to a thumbnail, followed by the link the event is about
+    pub fn card(
+        target: RenderTarget,
+        title: String,
+        description: String,
+        avatar_url: &str,
+        url: &str,
+    ) -> Self {
+        let mut message = Self::new(target);
+        message.push(RenderedBlock::Section {
+            texts: vec![title, description],
+            accessory: RenderedAccessory::Thumbnail {
+                url: avatar_url.to_string(),
+            },
+        });
+        message.footer(url);
+
+        message
+    }
+
+    #[inline]
+    pub fn push(&mut self, block: RenderedBlock) {
+        self.blocks.push(block);
+    }
+
+    #[inline]
+    pub fn footer(&mut self, url: &str) {
+        self.push(RenderedBlock::Text {
+            content: format!("-# {url}"),
+        });
+    }
+
+    pub fn to_component(&self) -> CreateComponent<'static> {
+        let container_components = self
+            .blocks
+            .iter()
+            .map(|block| match block {
+                RenderedBlock::Text { content } => {
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(content.clone()))
+                }
+                RenderedBlock::Section { texts, accessory } => {
+                    CreateContainerComponent::Section(CreateSection::new(
+                        texts
+                            .iter()
+                            .map(|text| {
+                                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                                    text.clone(),
+                                ))
+                            })
+                            .collect::<Vec<_>>(),
+                        match accessory {
+                            RenderedAccessory::Thumbnail { url } => {
+                                CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
+                                    CreateUnfurledMediaItem::new(url.clone()),
+                                ))
+                            }
+                            RenderedAccessory::Link { label, url } => {
+                                CreateSectionAccessory::Button(
+                                    CreateButton::new_link(url.clone()).label(label.clone()),
+                                )
+                            }
+                        },
+                    ))
+                }
+                RenderedBlock::Separator => {
+                    CreateContainerComponent::Separator(CreateSeparator::new())
+                }
+            })
+            .collect::<Vec<_>>();
+
+        CreateComponent::Container(CreateContainer::new(container_components))
+    }
+}
+
+/// Turns a webhook event into a message without touching the database or Discord
+pub trait EventRenderer {
+    /// `None` if the event is not worth announcing, e.g. an action nobody cares about
+    fn render(&self, event: &WebhookEvent) -> Result<Option<RenderedMessage>, anyhow::Error>;
+}
+
+pub struct EventRendererList {
+    renderers: Vec<(WebhookEventType, Box<dyn EventRenderer + Send + Sync>)>,
+}
+
+impl EventRendererList {
+    pub fn new() -> Self {
+        Self {
+            renderers: Vec::new(),
+        }
+    }
+
+    pub fn add_renderer(
+        mut self,
+        kind: WebhookEventType,
+        renderer: impl EventRenderer + Send + Sync + 'static,
+    ) -> Self {
+        self.renderers.push((kind, Box::new(renderer)));
+        self
+    }
+
+    pub fn renderer(&self, kind: &WebhookEventType) -> Option<&(dyn EventRenderer + Send + Sync)> {
+        self.renderers
+            .iter()
+            .find(|(renderer_kind, _)| renderer_kind == kind)
+            .map(|(_, renderer)| renderer.as_ref())
+    }
+}
+
+impl std::fmt::Debug for EventRendererList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.renderers.iter().map(|(kind, _)| kind))
+            .finish()
+    }
+}
+
+pub fn renderers(list: EventRendererList) -> EventRendererList {
+    list.add_renderer(WebhookEventType::Release, release::Release)
+        .add_renderer(WebhookEventType::Create, reference::CreateReference)
+        .add_renderer(WebhookEventType::Delete, reference::DeleteReference)
+        .add_renderer(WebhookEventType::Organization, organization::Organization)
+        .add_renderer(WebhookEventType::Membership, organization::Membership)
+        .add_renderer(WebhookEventType::Team, organization::Team)
+        .add_renderer(WebhookEventType::Repository, repository::Repository)
+        .add_renderer(WebhookEventType::Fork, repository::Fork)
+}
+
+fn sender_link(event: &WebhookEvent) -> String {
+    event.sender.as_ref().map_or_else(
+        || "**Someone**".to_string(),
+        |sender| format!("[**{}**]({})", sender.login, sender.html_url),
+    )
+}
+
+/// The organization's avatar, or the sender's for events outside of one
+fn avatar_url(event: &WebhookEvent) -> String {
+    event
+        .organization
+        .as_ref()
+        .map(|organization| organization.avatar_url.to_string())
+        .or_else(|| {
+            event
+                .sender
+                .as_ref()
+                .map(|sender| sender.avatar_url.to_string())
+        })
+        .unwrap_or_default()
+}
+
+fn organization_login(event: &WebhookEvent) -> String {
+    event
+        .organization
+        .as_ref()
+        .map_or_else(String::new, |organization| organization.login.clone())
+}
+
+fn repository_name(event: &WebhookEvent) -> Option<String> {
+    event.repository.as_ref().map(|repository| {
+        repository
+            .full_name
+            .clone()
+            .unwrap_or_else(|| repository.name.clone())
+    })
+}
+
+fn repository_url(event: &WebhookEvent) -> Option<String> {
+    event.repository.as_ref().map(|repository| {
+        repository
+            .html_url
+            .as_ref()
+            .map_or_else(|| repository.name.clone(), |h| h.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    fn render(name: &str, event: &str) -> Option<super::RenderedMessage> {
+        let payload = std::fs::read_to_string(format!(
+            "{}/tests/fixtures/github/{name}.json",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let event = octocrab::models::webhook_events::WebhookEvent::try_from_header_and_body(
+            event, &payload,
+        )
+        .unwrap();
+
+        super::renderers(super::EventRendererList::new())
+            .renderer(&event.kind)
+            .unwrap()
+            .render(&event)
+            .unwrap()
+    }
+
+    #[test]
+    fn release_published() {
+        insta::assert_json_snapshot!(render("release_published", "release"));
+    }
+
+    #[test]
+    fn release_draft_is_skipped() {
+        assert_eq!(render("release_draft", "release"), None);
+    }
+
+    #[test]
+    fn create_branch() {
+        insta::assert_json_snapshot!(render("create_branch", "create"));
+    }
+
+    #[test]
+    fn delete_tag() {
+        insta::assert_json_snapshot!(render("delete_tag", "delete"));
+    }
+
+    #[test]
+    fn organization_member_added() {
+        insta::assert_json_snapshot!(render("organization_member_added", "organization"));
+    }
+
+    #[test]
+    fn team_added_to_repository() {
+        insta::assert_json_snapshot!(render("team_added_to_repository", "team"));
+    }
+
+    #[test]
+    fn repository_archived() {
+        insta::assert_json_snapshot!(render("repository_archived", "repository"));
+    }
+
+    #[test]
+    fn fork() {
+        insta::assert_json_snapshot!(render("fork", "fork"));
+    }
+}
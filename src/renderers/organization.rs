@@ -0,0 +1,176 @@
+use super::{RenderTarget, RenderedMessage};
+use octocrab::models::webhook_events::{
+    WebhookEvent, WebhookEventPayload,
+    payload::{
+        MembershipWebhookEventAction, OrganizationWebhookEventAction, TeamWebhookEventAction,
+    },
+};
+use serde::Deserialize;
+
+fn admin_card(event: &WebhookEvent, title: &str, description: String) -> RenderedMessage {
+    RenderedMessage::card(
+        RenderTarget::Admin,
+        format!("## <:hammer:1150889684227076227> {title}"),
+        description,
+        &super::avatar_url(event),
+        &format!("https://github.com/{}", super::organization_login(event)),
+    )
+}
+
+pub struct Organization;
+
+impl super::EventRenderer for Organization {
+    fn render(&self, event: &WebhookEvent) -> Result<Option<RenderedMessage>, anyhow::Error> {
+        let WebhookEventPayload::Organization(organization) = &event.specific else {
+            return Ok(None);
+        };
+
+        #[derive(Deserialize)]
+        struct MembershipUser {
+            login: String,
+            html_url: String,
+        }
+
+        #[derive(Deserialize)]
+        struct MembershipData {
+            user: Option<MembershipUser>,
+            role: Option<String>,
+        }
+
+        let membership = organization
+            .membership
+            .clone()
+            .map(serde_json::from_value::<MembershipData>)
+            .transpose()?;
+        let member = membership
+            .as_ref()
+            .and_then(|membership| membership.user.as_ref())
+            .map_or_else(
+                || "a member".to_string(),
+                |user| format!("[**{}**]({})", user.login, user.html_url),
+            );
+        let role = membership
+            .as_ref()
+            .and_then(|membership| membership.role.as_deref())
+            .map_or_else(String::new, |role| format!(" as `{role}`"));
+
+        let sender = super::sender_link(event);
+        let organization_login = super::organization_login(event);
+
+        let (title, description) = match organization.action {
+            OrganizationWebhookEventAction::MemberAdded => (
+                "Member added",
+                format!("{sender} added {member} to `{organization_login}`{role}"),
+            ),
+            OrganizationWebhookEventAction::MemberInvited => (
+                "Member invited",
+                format!("{sender} invited a new member to `{organization_login}`"),
+            ),
+            OrganizationWebhookEventAction::MemberRemoved => (
+                "Member removed",
+                format!("{sender} removed {member} from `{organization_login}`"),
+            ),
+            OrganizationWebhookEventAction::Renamed => (
+                "Organization renamed",
+                format!("{sender} renamed the organization to `{organization_login}`"),
+            ),
+            OrganizationWebhookEventAction::Deleted => (
+                "Organization deleted",
+                format!("{sender} deleted `{organization_login}`"),
+            ),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(admin_card(event, title, description)))
+    }
+}
+
+pub struct Membership;
+
+impl super::EventRenderer for Membership {
+    fn render(&self, event: &WebhookEvent) -> Result<Option<RenderedMessage>, anyhow::Error> {
+        let WebhookEventPayload::Membership(membership) = &event.specific else {
+            return Ok(None);
+        };
+
+        #[derive(Deserialize)]
+        struct MemberData {
+            login: String,
+            html_url: String,
+        }
+
+        #[derive(Deserialize)]
+        struct TeamData {
+            name: String,
+        }
+
+        let member: MemberData = serde_json::from_value(membership.member.clone())?;
+        let team: TeamData = serde_json::from_value(membership.team.clone())?;
+        let member = format!("[**{}**]({})", member.login, member.html_url);
+        let sender = super::sender_link(event);
+
+        let (title, description) = match membership.action {
+            MembershipWebhookEventAction::Added => (
+                "Team member added",
+                format!("{sender} added {member} to the team `{}`", team.name),
+            ),
+            MembershipWebhookEventAction::Removed => (
+                "Team member removed",
+                format!("{sender} removed {member} from the team `{}`", team.name),
+            ),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(admin_card(event, title, description)))
+    }
+}
+
+pub struct Team;
+
+impl super::EventRenderer for Team {
+    fn render(&self, event: &WebhookEvent) -> Result<Option<RenderedMessage>, anyhow::Error> {
+        let WebhookEventPayload::Team(team) = &event.specific else {
+            return Ok(None);
+        };
+
+        #[derive(Deserialize)]
+        struct TeamData {
+            name: String,
+            html_url: Option<String>,
+        }
+
+        let team_data: TeamData = serde_json::from_value(team.team.clone())?;
+        let team_name = team_data.html_url.as_ref().map_or_else(
+            || format!("`{}`", team_data.name),
+            |html_url| format!("[`{}`]({html_url})", team_data.name),
+        );
+        let repository = super::repository_name(event).unwrap_or_default();
+        let sender = super::sender_link(event);
+
+        let (title, description) = match team.action {
+            TeamWebhookEventAction::Created => (
+                "Team created",
+                format!("{sender} created the team {team_name}"),
+            ),
+            TeamWebhookEventAction::Deleted => (
+                "Team deleted",
+                format!("{sender} deleted the team {team_name}"),
+            ),
+            TeamWebhookEventAction::Edited => (
+                "Team edited",
+                format!("{sender} edited the team {team_name}"),
+            ),
+            TeamWebhookEventAction::AddedToRepository => (
+                "Team added to repository",
+                format!("{sender} gave the team {team_name} access to `{repository}`"),
+            ),
+            TeamWebhookEventAction::RemovedFromRepository => (
+                "Team removed from repository",
+                format!("{sender} removed the team {team_name} from `{repository}`"),
+            ),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(admin_card(event, title, description)))
+    }
+}
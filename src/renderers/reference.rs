@@ -0,0 +1,83 @@
+use super::{RenderTarget, RenderedAccessory, RenderedBlock, RenderedMessage};
+use octocrab::models::webhook_events::{WebhookEvent, WebhookEventPayload, payload::RefType};
+
+fn ref_kind(ref_type: &RefType) -> Option<&'static str> {
+    match ref_type {
+        RefType::Tag => Some("Tag"),
+        RefType::Branch => Some("Branch"),
+        _ => None,
+    }
+}
+
+pub struct CreateReference;
+
+impl super::EventRenderer for CreateReference {
+    fn render(&self, event: &WebhookEvent) -> Result<Option<RenderedMessage>, anyhow::Error> {
+        let WebhookEventPayload::Create(create) = &event.specific else {
+            return Ok(None);
+        };
+        let Some(kind) = ref_kind(&create.ref_type) else {
+            return Ok(None);
+        };
+
+        let repository_url = super::repository_url(event).unwrap_or_default();
+
+        let mut message = RenderedMessage::new(RenderTarget::Routed);
+        message.push(RenderedBlock::Section {
+            texts: vec![
+                format!(
+                    "## <:package:1150890021516234832> {kind} `{}` created",
+                    create.r#ref
+                ),
+                format!(
+                    "{} created the {} [`{}`]({repository_url}/tree/{}).",
+                    super::sender_link(event),
+                    kind.to_lowercase(),
+                    create.r#ref,
+                    create.r#ref
+                ),
+            ],
+            accessory: RenderedAccessory::Thumbnail {
+                url: super::avatar_url(event),
+            },
+        });
+        message.footer(&repository_url);
+
+        Ok(Some(message))
+    }
+}
+
+pub struct DeleteReference;
+
+impl super::EventRenderer for DeleteReference {
+    fn render(&self, event: &WebhookEvent) -> Result<Option<RenderedMessage>, anyhow::Error> {
+        let WebhookEventPayload::Delete(delete) = &event.specific else {
+            return Ok(None);
+        };
+        let Some(kind) = ref_kind(&delete.ref_type) else {
+            return Ok(None);
+        };
+
+        let mut message = RenderedMessage::new(RenderTarget::Routed);
+        message.push(RenderedBlock::Section {
+            texts: vec![
+                format!(
+                    "## <:package:1150890021516234832> {kind} `{}` deleted",
+                    delete.r#ref
+                ),
+                format!(
+                    "{} deleted the {} `{}`.",
+                    super::sender_link(event),
+                    kind.to_lowercase(),
+                    delete.r#ref
+                ),
+            ],
+            accessory: RenderedAccessory::Thumbnail {
+                url: super::avatar_url(event),
+            },
+        });
+        message.footer(&super::repository_url(event).unwrap_or_default());
+
+        Ok(Some(message))
+    }
+}
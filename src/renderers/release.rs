@@ -0,0 +1,140 @@
+use super::{RenderTarget, RenderedAccessory, RenderedBlock, RenderedMessage};
+use octocrab::models::webhook_events::{
+    WebhookEvent, WebhookEventPayload, payload::ReleaseWebhookEventAction,
+};
+use serde::Deserialize;
+
+fn format_bytes(bytes: i64) -> String {
+    match bytes {
+        ..1_024 => format!("{bytes} B"),
+        ..1_048_576 => format!("{:.1} KB", bytes as f64 / 1_024.0),
+        ..1_073_741_824 => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+        _ => format!("{:.1} GB", bytes as f64 / 1_073_741_824.0),
+    }
+}
+
+/// First few lines of release notes, with headings flattened so they do not dominate the message
+fn release_notes_excerpt(body: &str) -> String {
+    let mut excerpt = String::new();
+
+    for line in body.lines().filter(|line| !line.trim().is_empty()).take(12) {
+        let line = line.trim_end();
+
+        match line.trim_start_matches('#') {
+            heading if heading.len() != line.len() => {
+                excerpt.push_str(&format!("**{}**\n", heading.trim()));
+            }
+            _ => {
+                excerpt.push_str(line);
+                excerpt.push('\n');
+            }
+        }
+    }
+
+    crate::utils::truncate_text(&excerpt, 1000)
+}
+
+pub struct Release;
+
+impl super::EventRenderer for Release {
+    fn render(&self, event: &WebhookEvent) -> Result<Option<RenderedMessage>, anyhow::Error> {
+        let WebhookEventPayload::Release(release) = &event.specific else {
+            return Ok(None);
+        };
+
+        #[derive(Deserialize)]
+        struct ReleaseAsset {
+            name: String,
+            browser_download_url: String,
+            size: i64,
+        }
+
+        #[derive(Deserialize)]
+        struct ReleaseData {
+            tag_name: String,
+            name: Option<String>,
+            html_url: String,
+            body: Option<String>,
+            draft: bool,
+            prerelease: bool,
+            #[serde(default)]
+            assets: Vec<ReleaseAsset>,
+        }
+
+        let release_data: ReleaseData = serde_json::from_value(release.release.clone())?;
+        if release_data.draft {
+            return Ok(None);
+        }
+
+        let kind = if release_data.prerelease {
+            "Pre-release"
+        } else {
+            "Release"
+        };
+        // published pre-releases also fire `prereleased`, only announce them once
+        let verb = match release.action {
+            ReleaseWebhookEventAction::Published if !release_data.prerelease => "published",
+            ReleaseWebhookEventAction::Prereleased => "published",
+            ReleaseWebhookEventAction::Edited => "edited",
+            _ => return Ok(None),
+        };
+
+        let name = release_data
+            .name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| release_data.tag_name.clone());
+
+        let mut message = RenderedMessage::new(RenderTarget::Routed);
+        message.push(RenderedBlock::Section {
+            texts: vec![
+                format!("## <:package:1150890021516234832> {kind} `{name}` {verb}"),
+                format!(
+                    "{} {verb} the {} [`{}`]({}).",
+                    super::sender_link(event),
+                    kind.to_lowercase(),
+                    release_data.tag_name,
+                    release_data.html_url
+                ),
+            ],
+            accessory: RenderedAccessory::Thumbnail {
+                url: super::avatar_url(event),
+            },
+        });
+
+        if let Some(body) = release_data.body.filter(|body| !body.trim().is_empty()) {
+            message.push(RenderedBlock::Separator);
+            message.push(RenderedBlock::Text {
+                content: release_notes_excerpt(&body),
+            });
+        }
+
+        if !release_data.assets.is_empty() {
+            let mut asset_string = String::from("### Assets\n");
+
+            for asset in release_data.assets.iter().take(10) {
+                asset_string.push_str(&format!(
+                    "[`{}`]({}) ({})\n",
+                    asset.name,
+                    asset.browser_download_url,
+                    format_bytes(asset.size)
+                ));
+            }
+
+            if release_data.assets.len() > 10 {
+                asset_string.push_str(&format!(
+                    "-# ... {} more Assets\n",
+                    release_data.assets.len() - 10
+                ));
+            }
+
+            message.push(RenderedBlock::Separator);
+            message.push(RenderedBlock::Text {
+                content: asset_string,
+            });
+        }
+
+        message.footer(&super::repository_url(event).unwrap_or_default());
+
+        Ok(Some(message))
+    }
+}
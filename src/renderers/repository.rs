@@ -0,0 +1,105 @@
+use super::{RenderTarget, RenderedMessage};
+use octocrab::models::webhook_events::{
+    WebhookEvent, WebhookEventPayload, payload::RepositoryWebhookEventAction,
+};
+
+pub struct Repository;
+
+impl super::EventRenderer for Repository {
+    fn render(&self, event: &WebhookEvent) -> Result<Option<RenderedMessage>, anyhow::Error> {
+        let WebhookEventPayload::Repository(repository_event) = &event.specific else {
+            return Ok(None);
+        };
+        let Some(repository) = &event.repository else {
+            return Ok(None);
+        };
+
+        let organization_login = super::organization_login(event);
+        let repository_url = repository.html_url.as_ref().map_or_else(
+            || format!("https://github.com/{organization_login}"),
+            |h| h.to_string(),
+        );
+        let repository_link = format!(
+            "[`{}`]({repository_url})",
+            super::repository_name(event).unwrap_or_default()
+        );
+        let sender = super::sender_link(event);
+
+        let (title, description) = match repository_event.action {
+            RepositoryWebhookEventAction::Created => (
+                "Repository created",
+                format!("{sender} created {repository_link}"),
+            ),
+            RepositoryWebhookEventAction::Deleted => (
+                "Repository deleted",
+                format!("{sender} deleted {repository_link}"),
+            ),
+            RepositoryWebhookEventAction::Archived => (
+                "Repository archived",
+                format!("{sender} archived {repository_link}"),
+            ),
+            RepositoryWebhookEventAction::Unarchived => (
+                "Repository unarchived",
+                format!("{sender} unarchived {repository_link}"),
+            ),
+            RepositoryWebhookEventAction::Publicized => (
+                "Repository made public",
+                format!("{sender} made {repository_link} public"),
+            ),
+            RepositoryWebhookEventAction::Privatized => (
+                "Repository made private",
+                format!("{sender} made {repository_link} private"),
+            ),
+            RepositoryWebhookEventAction::Renamed => (
+                "Repository renamed",
+                format!("{sender} renamed the repository to {repository_link}"),
+            ),
+            RepositoryWebhookEventAction::Transferred => (
+                "Repository transferred",
+                format!("{sender} transferred {repository_link} to `{organization_login}`"),
+            ),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(RenderedMessage::card(
+            RenderTarget::Admin,
+            format!("## <:storage:1150889889294991381> {title}"),
+            description,
+            &super::avatar_url(event),
+            &repository_url,
+        )))
+    }
+}
+
+pub struct Fork;
+
+impl super::EventRenderer for Fork {
+    fn render(&self, event: &WebhookEvent) -> Result<Option<RenderedMessage>, anyhow::Error> {
+        let WebhookEventPayload::Fork(fork) = &event.specific else {
+            return Ok(None);
+        };
+
+        let forkee_name = fork
+            .forkee
+            .full_name
+            .clone()
+            .unwrap_or_else(|| fork.forkee.name.clone());
+        let forkee_url = fork
+            .forkee
+            .html_url
+            .as_ref()
+            .map_or_else(String::new, |h| h.to_string());
+
+        Ok(Some(RenderedMessage::card(
+            RenderTarget::Admin,
+            "## <:storage:1150889889294991381> Repository forked".to_string(),
+            format!(
+                "{} forked `{}` to [`{forkee_name}`]({forkee_url})",
+                super::sender_link(event),
+                super::repository_name(event).unwrap_or_default()
+            ),
+            &super::avatar_url(event),
+            &forkee_url,
+        )))
+    }
+}
@@ -1,288 +1,2305 @@
 use super::State;
-use octocrab::models::webhook_events::{
-    WebhookEventPayload,
-    payload::{
-        IssuesWebhookEventAction, PullRequestWebhookEventAction, SponsorshipWebhookEventAction,
-        StarWebhookEventAction,
+use octocrab::models::{
+    issues::IssueStateReason,
+    pulls::ReviewState,
+    webhook_events::{
+        WebhookEventPayload,
+        payload::{
+            CommitState, DiscussionCommentWebhookEventAction, DiscussionWebhookEventAction,
+            IssueCommentWebhookEventAction, IssuesWebhookEventAction,
+            PullRequestReviewCommentWebhookEventAction, PullRequestReviewWebhookEventAction,
+            PullRequestWebhookEventAction, RefType, SecurityAdvisoryWebhookEventAction,
+            SponsorshipWebhookEventAction, StarWebhookEventAction, WorkflowRunWebhookEventAction,
+        },
     },
 };
 use serde::Deserialize;
 use serenity::all::{
-    CreateButton, CreateComponent, CreateContainer, CreateContainerComponent, CreateMessage,
-    CreateSection, CreateSectionAccessory, CreateSectionComponent, CreateSeparator,
-    CreateTextDisplay, CreateThumbnail, CreateUnfurledMediaItem, MessageFlags,
+    ChannelId, CreateActionRow, CreateAllowedMentions, CreateButton, CreateComponent,
+    CreateContainer, CreateContainerComponent, CreateForumPost, CreateMessage, CreateSection,
+    CreateSectionAccessory, CreateSectionComponent, CreateSeparator, CreateTextDisplay,
+    CreateThread, CreateThumbnail, CreateUnfurledMediaItem, EditThread, GenericChannelId,
+    MessageFlags, ThreadId,
 };
 use std::sync::LazyLock;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
-static GITHUB_TX: LazyLock<
-    tokio::sync::mpsc::UnboundedSender<(State, octocrab::models::webhook_events::WebhookEvent, u8)>,
-> = LazyLock::new(|| {
-    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<(
-        State,
-        octocrab::models::webhook_events::WebhookEvent,
-        u8,
-    )>();
+static GITHUB_EVENTS_NOTIFY: LazyLock<tokio::sync::Notify> =
+    LazyLock::new(tokio::sync::Notify::new);
 
-    let requeue_tx = tx.clone();
-    tokio::spawn(async move {
-        const MAX_ATTEMPTS: u8 = 3;
+const MAX_ATTEMPTS: i64 = 10;
 
-        while let Some((state, event, attempt)) = rx.recv().await {
-            let result = if event.repository.is_some() {
-                handle_repository_event(&state, event.clone()).await
-            } else {
-                handle_organization_event(&state, event.clone()).await
-            };
+#[inline]
+fn retry_delay(attempts: i64) -> chrono::Duration {
+    chrono::Duration::seconds((10 << (attempts - 1).clamp(0, 10)).min(3600))
+}
 
-            if let Err(err) = result {
-                tracing::error!(
-                    "failed to process github event (attempt {attempt}/{MAX_ATTEMPTS}): {:?}",
-                    err
-                );
+/// Channel for events no route matches, the source's own fallback if it has one
+fn fallback_channel_id(state: &State, source: Option<&str>) -> u64 {
+    source
+        .and_then(|source| {
+            state
+                .env
+                .github_sources
+                .iter()
+                .find(|github_source| github_source.name == source)
+        })
+        .and_then(|github_source| github_source.channel_id)
+        .unwrap_or(state.env.github_channel_id)
+}
 
-                if attempt < MAX_ATTEMPTS {
-                    let requeue_tx = requeue_tx.clone();
-                    tokio::spawn(async move {
-                        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+/// Channel a new message for an event goes to, messages the bot already tracks stay where they are
+async fn route_channel_id(
+    state: &State,
+    delivery: &crate::models::GithubWebhookEvent,
+    repository: &str,
+    event: &str,
+    branch: Option<&str>,
+) -> Result<u64, anyhow::Error> {
+    if let Some(replay_channel_id) = delivery.replay_channel_id {
+        return Ok(replay_channel_id);
+    }
 
-                        if let Err(err) = requeue_tx.send((state, event, attempt + 1)) {
-                            tracing::error!("failed to requeue github event: {:?}", err);
-                        }
-                    });
-                } else {
-                    tracing::error!("giving up on github event after {attempt} attempts");
-                }
-            }
+    crate::models::resolve_github_channel(
+        state.database.read(),
+        fallback_channel_id(state, delivery.source.as_deref()),
+        repository,
+        event,
+        branch,
+    )
+    .await
+}
+
+async fn process_github_event(
+    state: &State,
+    queued: &crate::models::GithubWebhookEvent,
+) -> Result<(), anyhow::Error> {
+    let event = octocrab::models::webhook_events::WebhookEvent::try_from_header_and_body(
+        &queued.event,
+        &queued.payload,
+    )?;
+
+    if matches!(
+        event.specific,
+        WebhookEventPayload::DependabotAlert(_)
+            | WebhookEventPayload::RepositoryVulnerabilityAlert(_)
+            | WebhookEventPayload::SecretScanningAlert(_)
+            | WebhookEventPayload::CodeScanningAlert(_)
+            | WebhookEventPayload::SecurityAdvisory(_)
+    ) {
+        if queued.replay_channel_id.is_some() {
+            return Err(anyhow::anyhow!(
+                "security alerts cannot be replayed into another channel"
+            ));
+        }
+
+        handle_security_event(state, event).await
+    } else if let Some(renderer) = state.renderers.renderer(&event.kind) {
+        if !reference_event_allowed(state, &event).await? {
+            return Ok(());
         }
-    });
 
-    tx
-});
+        let Some(rendered) = renderer.render(&event)? else {
+            return Ok(());
+        };
 
-async fn handle_repository_event(
+        send_rendered_event(state, queued, &event, &rendered).await
+    } else if event.repository.is_some() {
+        handle_repository_event(state, queued, event).await
+    } else {
+        handle_organization_event(state, queued, event).await
+    }
+}
+
+/// Rendered messages quote titles and bodies from GitHub, so nothing in them is allowed to ping
+async fn send_rendered_event(
     state: &State,
-    event: octocrab::models::webhook_events::WebhookEvent,
+    delivery: &crate::models::GithubWebhookEvent,
+    event: &octocrab::models::webhook_events::WebhookEvent,
+    rendered: &crate::renderers::RenderedMessage,
 ) -> Result<(), anyhow::Error> {
-    let Some(organization) = event.organization else {
-        return Err(anyhow::anyhow!(
-            "missing organization information in webhook payload"
-        ));
-    };
-    let Some(repository) = event.repository else {
-        return Err(anyhow::anyhow!(
-            "missing repository information in webhook payload"
-        ));
+    let target_channel_id = match rendered.target {
+        crate::renderers::RenderTarget::Admin => state.env.github_admin_channel_id,
+        crate::renderers::RenderTarget::Routed => None,
     };
-    let Some(sender) = event.sender else {
-        return Err(anyhow::anyhow!(
-            "missing sender information in webhook payload"
-        ));
+
+    let channel_id = match delivery.replay_channel_id.or(target_channel_id) {
+        Some(channel_id) => channel_id,
+        None => {
+            let repository_name = event.repository.as_ref().map_or_else(
+                || {
+                    event
+                        .organization
+                        .as_ref()
+                        .map_or_else(String::new, |organization| organization.login.clone())
+                },
+                |repository| {
+                    repository
+                        .full_name
+                        .clone()
+                        .unwrap_or_else(|| repository.name.clone())
+                },
+            );
+
+            route_channel_id(
+                state,
+                delivery,
+                &repository_name,
+                &delivery.event,
+                event_branch(&event.specific).as_deref(),
+            )
+            .await?
+        }
     };
 
-    let mut container_components = Vec::new();
-    let mut edit_github_message = None;
-    let mut create_github_message = None;
+    GenericChannelId::new(channel_id)
+        .send_message(
+            &*state.bot.read().await,
+            CreateMessage::new()
+                .components(&[rendered.to_component()])
+                .flags(MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
 
-    match event.specific {
-        WebhookEventPayload::Push(push) => {
-            let branch = push
-                .r#ref
-                .strip_prefix("refs/heads/")
-                .unwrap_or(&push.r#ref)
-                .to_string();
+    Ok(())
+}
 
-            let mut commit_string = String::new();
+async fn drain_github_events(state: &State) -> Result<usize, anyhow::Error> {
+    let events =
+        crate::models::due_github_webhook_events(state.database.read(), chrono::Utc::now(), 25)
+            .await?;
 
-            for commit in push.commits.iter().take(8) {
-                commit_string.push_str(&format!(
-                    "[`{}`]({}) ({}): {}\n",
-                    commit.id.chars().take(7).collect::<String>(),
-                    commit.url,
-                    commit.author.user.name,
-                    commit.message.lines().next().unwrap_or_default()
-                ));
+    for queued in &events {
+        let attempts = queued.attempts + 1;
+
+        match process_github_event(state, queued).await {
+            Ok(()) => {
+                crate::models::set_github_webhook_event_processed(
+                    state.database.write(),
+                    queued.id,
+                    attempts,
+                )
+                .await?;
             }
+            Err(err) => {
+                tracing::error!(
+                    "failed to process github {} event {} (attempt {attempts}/{MAX_ATTEMPTS}): {:?}",
+                    queued.event,
+                    queued.id,
+                    err
+                );
 
-            if push.commits.len() > 8 {
-                commit_string
-                    .push_str(&format!("-# ... {} more Commits\n", push.commits.len() - 8));
+                let next_attempt = if attempts < MAX_ATTEMPTS {
+                    Some(chrono::Utc::now() + retry_delay(attempts))
+                } else {
+                    tracing::error!(
+                        "giving up on github {} event {} after {attempts} attempts",
+                        queued.event,
+                        queued.id
+                    );
+                    sentry_anyhow::capture_anyhow(&err);
+
+                    None
+                };
+
+                crate::models::reschedule_github_webhook_event(
+                    state.database.write(),
+                    queued.id,
+                    attempts,
+                    next_attempt,
+                    &format!("{err:#}"),
+                )
+                .await?;
             }
+        }
+    }
 
-            container_components.push(CreateContainerComponent::Section(CreateSection::new(
-                vec![
-                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                        "## <:package:1150890021516234832> {} Commit{} pushed to `{}`",
-                        push.commits.len(),
-                        if push.commits.len() == 1 { "" } else { "s" },
-                        branch
-                    ))),
-                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(commit_string)),
-                ],
-                CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
-                    CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
-                )),
-            )));
+    Ok(events.len())
+}
 
-            if let Some(head_commit) = push.head_commit {
-                create_github_message = Some((head_commit, push.commits, branch));
-            }
+/// Runs a stored delivery through the handlers again, e.g. after its message was deleted
+/// or a rendering bug was fixed. Returns `None` if the delivery is unknown or was pruned.
+pub async fn replay_github_event(
+    state: &State,
+    delivery_id: &str,
+    channel_id: Option<u64>,
+) -> Result<Option<crate::models::GithubWebhookEvent>, anyhow::Error> {
+    let Some(mut delivery) =
+        crate::models::get_github_webhook_event(state.database.read(), delivery_id).await?
+    else {
+        return Ok(None);
+    };
+
+    delivery.replayed = true;
+    delivery.replay_channel_id = channel_id;
+
+    tracing::info!(
+        "replaying github {} delivery {delivery_id}{}",
+        delivery.event,
+        channel_id.map_or_else(String::new, |channel_id| format!(" into {channel_id}"))
+    );
+
+    process_github_event(state, &delivery).await?;
+
+    Ok(Some(delivery))
+}
+
+/// Milestones and digests replace the message per star when either is configured
+#[inline]
+fn count_stars_silently(state: &State) -> bool {
+    !state.env.github_star_milestones.is_empty() || state.env.github_star_digest.is_some()
+}
+
+async fn send_star_digests(state: &State, interval: chrono::Duration) -> Result<(), anyhow::Error> {
+    let due = crate::models::due_github_star_digests(
+        state.database.read(),
+        chrono::Utc::now() - interval,
+    )
+    .await?;
+
+    let mut channels: indexmap::IndexMap<u64, Vec<crate::models::GithubRepositoryStars>> =
+        indexmap::IndexMap::new();
+    for repository_stars in due {
+        let channel_id = crate::models::resolve_github_channel(
+            state.database.read(),
+            fallback_channel_id(state, repository_stars.source.as_deref()),
+            &repository_stars.repository,
+            "star",
+            None,
+        )
+        .await?;
+
+        channels
+            .entry(channel_id)
+            .or_default()
+            .push(repository_stars);
+    }
+
+    for (channel_id, repositories) in channels {
+        let mut digest_string = String::new();
+        for repository_stars in &repositories {
+            digest_string.push_str(&format!(
+                "[`{repository}`](https://github.com/{repository}): +{} / -{}, now `{}` stars\n",
+                repository_stars.starred,
+                repository_stars.unstarred,
+                repository_stars.stars,
+                repository = repository_stars.repository
+            ));
         }
-        WebhookEventPayload::Star(star) => match star.action {
-            StarWebhookEventAction::Created => {
-                pub static STAR_DEDUP_CACHE: LazyLock<moka::future::Cache<(u64, u64), ()>> =
-                    LazyLock::new(|| {
-                        moka::future::Cache::builder()
-                            .time_to_live(std::time::Duration::from_secs(600))
-                            .max_capacity(1000)
-                            .build()
-                    });
 
-                let dedup_key = (*repository.id, *sender.id);
-                if STAR_DEDUP_CACHE.get(&dedup_key).await.is_some() {
-                    return Ok(());
-                }
+        let since = repositories
+            .iter()
+            .map(|repository_stars| repository_stars.digest_started)
+            .min()
+            .unwrap_or_default();
 
-                STAR_DEDUP_CACHE.insert(dedup_key, ()).await;
+        let component = CreateComponent::Container(CreateContainer::new(vec![
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                "## <:star:1229766059381358623> Star digest",
+            )),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                crate::utils::truncate_text(&digest_string, 3500),
+            )),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
+                "-# Since <t:{}:f>",
+                since.timestamp()
+            ))),
+        ]));
 
-                container_components.push(CreateContainerComponent::Section(CreateSection::new(
-                    vec![
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
-                            "## <:star:1229766059381358623> Repository starred",
-                        )),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "[**{}**]({}) starred the repository!",
-                            sender.login, sender.html_url,
-                        ))),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "The new star count is `{}`.",
-                            repository.stargazers_count.unwrap_or(0)
-                        ))),
-                    ],
-                    CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
-                        CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
-                    )),
-                )));
+        GenericChannelId::new(channel_id)
+            .send_message(
+                &*state.bot.read().await,
+                CreateMessage::new()
+                    .components(&[component])
+                    .flags(MessageFlags::IS_COMPONENTS_V2)
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await?;
+
+        for repository_stars in &repositories {
+            crate::models::reset_github_star_digest(state.database.write(), repository_stars.id)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn spawn_github_star_digest_task(state: State) {
+    tokio::spawn(async move {
+        let Some(interval) = state.env.github_star_digest else {
+            return;
+        };
+
+        loop {
+            if let Err(err) = send_star_digests(&state, interval).await {
+                tracing::error!("failed to send github star digests: {:?}", err);
+                sentry_anyhow::capture_anyhow(&err);
             }
-            StarWebhookEventAction::Deleted => {
-                pub static UNSTAR_DEDUP_CACHE: LazyLock<moka::future::Cache<(u64, u64), ()>> =
-                    LazyLock::new(|| {
-                        moka::future::Cache::builder()
-                            .time_to_live(std::time::Duration::from_secs(600))
-                            .max_capacity(1000)
-                            .build()
-                    });
 
-                let dedup_key = (*repository.id, *sender.id);
-                if UNSTAR_DEDUP_CACHE.get(&dedup_key).await.is_some() {
-                    return Ok(());
+            tokio::time::sleep(std::time::Duration::from_hours(1)).await;
+        }
+    });
+}
+
+pub fn spawn_github_events_task(state: State) {
+    tokio::spawn(async move {
+        let mut last_prune = None::<std::time::Instant>;
+
+        loop {
+            if last_prune.is_none_or(|last_prune| last_prune.elapsed().as_secs() >= 3600) {
+                match crate::models::prune_github_webhook_events(
+                    state.database.write(),
+                    chrono::Utc::now() - state.env.github_delivery_retention,
+                )
+                .await
+                {
+                    Ok(pruned) if pruned > 0 => {
+                        tracing::debug!("pruned {pruned} old github webhook deliveries");
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        tracing::error!("failed to prune github webhook deliveries: {:?}", err);
+                    }
                 }
 
-                UNSTAR_DEDUP_CACHE.insert(dedup_key, ()).await;
+                last_prune = Some(std::time::Instant::now());
+            }
 
-                container_components.push(CreateContainerComponent::Section(CreateSection::new(
-                    vec![
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
-                            "## <:star:1229766059381358623> Repository unstarred",
-                        )),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "[**{}**]({}) unstarred the repository!",
-                            sender.login, sender.html_url,
-                        ))),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "The new star count is `{}`.",
-                            repository.stargazers_count.unwrap_or(0)
-                        ))),
-                    ],
-                    CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
-                        CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
-                    )),
-                )));
+            let drained = match drain_github_events(&state).await {
+                Ok(drained) => drained,
+                Err(err) => {
+                    tracing::error!("failed to drain github event queue: {:?}", err);
+                    sentry_anyhow::capture_anyhow(&err);
+
+                    0
+                }
+            };
+
+            if drained >= 25 {
+                continue;
             }
-            _ => {}
-        },
-        WebhookEventPayload::PullRequest(pull_request) => match pull_request.action {
-            PullRequestWebhookEventAction::Opened => {
-                container_components.push(CreateContainerComponent::Section(CreateSection::new(
-                    vec![
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
-                            "## <:storage:1150889889294991381> Pull Request opened",
-                        )),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "[**{}**]({}) opened a new pull request:",
-                            sender.login, sender.html_url,
-                        ))),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "[`#{} {}`]({})",
-                            pull_request.number,
-                            pull_request.pull_request.title.unwrap_or_default(),
-                            pull_request
-                                .pull_request
-                                .html_url
-                                .map_or_else(|| "".to_string(), |url| url.to_string()),
-                        ))),
-                    ],
-                    CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
-                        CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
-                    )),
-                )));
+
+            let wait = match crate::models::next_github_webhook_event_attempt(state.database.read())
+                .await
+            {
+                Ok(Some(next_attempt)) => (next_attempt - chrono::Utc::now())
+                    .to_std()
+                    .unwrap_or_default()
+                    .min(std::time::Duration::from_mins(1)),
+                _ => std::time::Duration::from_mins(1),
+            };
+
+            tokio::select! {
+                _ = GITHUB_EVENTS_NOTIFY.notified() => {},
+                _ = tokio::time::sleep(wait) => {},
             }
-            PullRequestWebhookEventAction::Reopened => {
-                container_components.push(CreateContainerComponent::Section(CreateSection::new(
-                    vec![
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
-                            "## <:storage:1150889889294991381> Pull Request reopened",
-                        )),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "[**{}**]({}) reopened a pull request:",
-                            sender.login, sender.html_url,
-                        ))),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "[`#{} {}`]({})",
-                            pull_request.number,
-                            pull_request.pull_request.title.unwrap_or_default(),
-                            pull_request
-                                .pull_request
-                                .html_url
-                                .map_or_else(|| "".to_string(), |url| url.to_string()),
-                        ))),
-                    ],
-                    CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
-                        CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
-                    )),
-                )));
+        }
+    });
+}
+
+#[derive(Deserialize)]
+struct WorkflowJobData {
+    id: i64,
+    run_id: i64,
+    name: String,
+    head_sha: String,
+    status: octocrab::models::workflows::Status,
+    conclusion: Option<String>,
+    started_at: Option<chrono::DateTime<chrono::Utc>>,
+    completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A CI result from a workflow job, check run, check suite or commit status
+struct WorkflowUpdate {
+    /// Workflow jobs are keyed by their plain id, everything else by `source:id`
+    key: String,
+    name: String,
+    source: crate::models::WorkflowSource,
+    app: Option<String>,
+    head_sha: String,
+    status: octocrab::models::workflows::Status,
+    /// `None` while the job is still running
+    conclusion: Option<String>,
+    started_at: Option<chrono::DateTime<chrono::Utc>>,
+    completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    failed_step: Option<String>,
+    /// Repository and id of a failed actions job, its failing step is only looked up
+    /// once the update is known to apply to a tracked message
+    failed_job: Option<(String, i64)>,
+}
+
+/// Check conclusions are a superset of the workflow ones, so they are matched as strings
+fn conclusion_succeeded(conclusion: &str) -> bool {
+    matches!(conclusion, "success" | "neutral" | "skipped")
+}
+
+/// Check run and suite statuses include values workflow jobs never use
+fn check_status(status: &str) -> octocrab::models::workflows::Status {
+    match status {
+        "completed" => octocrab::models::workflows::Status::Completed,
+        "in_progress" => octocrab::models::workflows::Status::InProgress,
+        _ => octocrab::models::workflows::Status::Queued,
+    }
+}
+
+/// Folds a CI update into the tracked jobs, never letting a finished job go back to running
+fn update_workflow_status(
+    workflow_status: &mut indexmap::IndexMap<String, crate::models::WorkflowStatus>,
+    workflow_update: &WorkflowUpdate,
+) {
+    let entry = workflow_status
+        .entry(workflow_update.key.clone())
+        .or_insert_with(|| crate::models::WorkflowStatus {
+            name: workflow_update.name.clone(),
+            status: octocrab::models::workflows::Status::Queued,
+            started: workflow_update.started_at.unwrap_or_else(chrono::Utc::now),
+            source: workflow_update.source,
+            app: workflow_update.app.clone(),
+            completed: None,
+            conclusion: None,
+            failed_step: None,
+        });
+
+    if let Some(started_at) = workflow_update.started_at {
+        entry.started = started_at;
+    }
+    if let Some(completed_at) = workflow_update.completed_at {
+        entry.completed = Some(completed_at);
+    }
+    if let Some(failed_step) = &workflow_update.failed_step {
+        entry.failed_step = Some(failed_step.clone());
+    }
+
+    let status = if let Some(conclusion) = &workflow_update.conclusion {
+        entry.conclusion = Some(conclusion.clone());
+
+        if conclusion_succeeded(conclusion) {
+            octocrab::models::workflows::Status::Completed
+        } else {
+            octocrab::models::workflows::Status::Failed
+        }
+    } else {
+        match (&entry.status, &workflow_update.status) {
+            (octocrab::models::workflows::Status::Completed, _) => {
+                octocrab::models::workflows::Status::Completed
             }
-            PullRequestWebhookEventAction::Closed => {
-                container_components.push(CreateContainerComponent::Section(CreateSection::new(
-                    vec![
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
-                            "## <:storage:1150889889294991381> Pull Request closed",
-                        )),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "[**{}**]({}) closed a pull request:",
-                            sender.login, sender.html_url,
-                        ))),
-                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                            "[`#{} {}`]({})",
-                            pull_request.number,
-                            pull_request.pull_request.title.unwrap_or_default(),
-                            pull_request
-                                .pull_request
-                                .html_url
-                                .map_or_else(|| "".to_string(), |url| url.to_string()),
-                        ))),
-                    ],
-                    CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
-                        CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
-                    )),
-                )));
+            (octocrab::models::workflows::Status::Failed, _) => {
+                octocrab::models::workflows::Status::Failed
             }
-            _ => {}
-        },
+            (
+                octocrab::models::workflows::Status::InProgress,
+                octocrab::models::workflows::Status::Queued
+                | octocrab::models::workflows::Status::Pending
+                | octocrab::models::workflows::Status::Waiting,
+            ) => octocrab::models::workflows::Status::InProgress,
+            _ => workflow_update.status.clone(),
+        }
+    };
+    entry.status = status;
+}
+
+/// The failing step is only extra detail, so a broken client or api error just leaves it out
+async fn failed_job_step(state: &State, repository: &str, job_id: i64) -> Option<String> {
+    let client = match crate::github::client(&state.env) {
+        Ok(client) => client?,
+        Err(err) => {
+            tracing::warn!("failed to create github client for job {job_id}: {:?}", err);
+
+            return None;
+        }
+    };
+
+    crate::github::failed_job_step(&client, repository, job_id)
+        .await
+        .unwrap_or_else(|err| {
+            tracing::warn!("failed to fetch steps of github job {job_id}: {:?}", err);
+
+            None
+        })
+}
+
+/// Applies a CI update to every open pull request and the push message for its commit,
+/// returning the push message so the caller can re-render it.
+/// Updates nothing is waiting for yet are deferred until a message for the commit is created.
+async fn apply_workflow_update(
+    state: &State,
+    delivery: &crate::models::GithubWebhookEvent,
+    repository_id: i64,
+    avatar_url: &str,
+    mut workflow_update: WorkflowUpdate,
+) -> Result<Option<crate::models::GithubMessage>, anyhow::Error> {
+    let dry_run = delivery.replay_channel_id.is_some();
+
+    // a dry run only shows the push message, the pull requests would each get a message too
+    let github_pull_requests = if dry_run {
+        Vec::new()
+    } else {
+        crate::models::github_pull_requests_by_head_sha(
+            state.database.read(),
+            repository_id,
+            &workflow_update.head_sha,
+        )
+        .await?
+    };
+    let github_message: Option<crate::models::GithubMessage> = sqlx::query_as(
+        "SELECT * FROM github_messages WHERE repository_id = ? AND workflow_sha = ?",
+    )
+    .bind(repository_id)
+    .bind(&workflow_update.head_sha)
+    .fetch_optional(state.database.read())
+    .await?;
+
+    if github_pull_requests.is_empty() && github_message.is_none() {
+        if !dry_run {
+            crate::models::defer_github_webhook_event(
+                state.database.write(),
+                delivery.id,
+                repository_id,
+                &workflow_update.head_sha,
+            )
+            .await?;
+        }
+
+        return Ok(None);
+    }
+
+    if let Some((repository, job_id)) = workflow_update.failed_job.take() {
+        workflow_update.failed_step = failed_job_step(state, &repository, job_id).await;
+    }
+
+    for mut github_pull_request in github_pull_requests {
+        update_workflow_status(&mut github_pull_request.workflow_status, &workflow_update);
+
+        sync_pull_request_message(state, delivery, &mut github_pull_request, avatar_url).await?;
+    }
+
+    let Some(mut github_message) = github_message else {
+        return Ok(None);
+    };
+
+    update_workflow_status(&mut github_message.workflow_status, &workflow_update);
+
+    if dry_run {
+        return Ok(Some(github_message));
+    }
+
+    sqlx::query("UPDATE github_messages SET workflow_status = ? WHERE id = ?")
+        .bind(serde_json::to_string(&github_message.workflow_status)?)
+        .bind(github_message.id)
+        .execute(state.database.write())
+        .await?;
+
+    Ok(Some(github_message))
+}
+
+fn workflow_status_string(
+    workflow_status: &indexmap::IndexMap<String, crate::models::WorkflowStatus>,
+) -> String {
+    let mut workflow_status_string = String::new();
+
+    for workflow_status in workflow_status.values() {
+        let mut details = String::new();
+
+        if let Some(completed) = workflow_status.completed {
+            details.push_str(&format!(
+                " · took {}",
+                crate::utils::format_duration(completed - workflow_status.started)
+            ));
+        }
+        if let Some(conclusion) = workflow_status
+            .conclusion
+            .as_deref()
+            .filter(|conclusion| !conclusion_succeeded(conclusion))
+        {
+            details.push_str(&format!(" · `{}`", conclusion.replace('_', " ")));
+        }
+        if let Some(failed_step) = &workflow_status.failed_step {
+            details.push_str(&format!(" in `{failed_step}`"));
+        }
+
+        workflow_status_string.push_str(&format!(
+            "{} **{}**{} <t:{}:R>{details}\n",
+            match workflow_status.status {
+                octocrab::models::workflows::Status::Completed => "<:accept:1156939740654878750>",
+                octocrab::models::workflows::Status::InProgress => {
+                    "<a:loading:1154135013948915793>"
+                }
+                octocrab::models::workflows::Status::Failed => "<:deny:1156939743230173234>",
+                _ => "<:clock:1150889651914158111>",
+            },
+            workflow_status.name,
+            match (workflow_status.source, &workflow_status.app) {
+                (crate::models::WorkflowSource::Actions, _) => "".to_string(),
+                (_, Some(app)) => format!(" · `{app}`"),
+                (crate::models::WorkflowSource::Status, None) => " · `Commit Status`".to_string(),
+                (_, None) => " · `Checks`".to_string(),
+            },
+            workflow_status.started.timestamp()
+        ));
+    }
+
+    workflow_status_string
+}
+
+/// Loads the row tracking a pull request, creating it in the routed channel if it is new.
+/// Dry runs get a copy detached from the tracked message, so a fresh one is sent and nothing is saved
+async fn load_github_pull_request(
+    state: &State,
+    delivery: &crate::models::GithubWebhookEvent,
+    repository_name: &str,
+    repository_id: i64,
+    pull_request: &octocrab::models::pulls::PullRequest,
+) -> Result<crate::models::GithubPullRequest, anyhow::Error> {
+    let github_pull_request = crate::models::get_github_pull_request(
+        state.database.read(),
+        repository_id,
+        pull_request.number as i64,
+    )
+    .await?;
+
+    if let Some(replay_channel_id) = delivery.replay_channel_id {
+        return Ok(match github_pull_request {
+            Some(github_pull_request) => crate::models::GithubPullRequest {
+                channel_id: replay_channel_id as i64,
+                message_id: None,
+                thread_id: None,
+                ..github_pull_request
+            },
+            None => crate::models::GithubPullRequest::unsaved(
+                repository_id,
+                pull_request.number as i64,
+                replay_channel_id,
+            ),
+        });
+    }
+
+    if let Some(github_pull_request) = github_pull_request {
+        return Ok(github_pull_request);
+    }
+
+    let channel_id = route_channel_id(
+        state,
+        delivery,
+        repository_name,
+        "pull_request",
+        Some(&pull_request.base.ref_field),
+    )
+    .await?;
+
+    crate::models::create_github_pull_request(
+        state.database.write(),
+        repository_id,
+        pull_request.number as i64,
+        channel_id,
+    )
+    .await
+}
+
+fn pull_request_components(
+    github_pull_request: &crate::models::GithubPullRequest,
+    avatar_url: &str,
+) -> Vec<CreateContainerComponent<'static>> {
+    use crate::models::PullRequestState;
+
+    let changes_requested = github_pull_request
+        .reviews
+        .values()
+        .any(|review| review.state == ReviewState::ChangesRequested);
+    let approved = github_pull_request
+        .reviews
+        .values()
+        .any(|review| review.state == ReviewState::Approved);
+
+    let status = match github_pull_request.state {
+        PullRequestState::Draft => "<:clock:1150889651914158111> Draft",
+        PullRequestState::Merged => "<:accept:1156939740654878750> Merged",
+        PullRequestState::Closed => "<:deny:1156939743230173234> Closed",
+        PullRequestState::Open if changes_requested => {
+            "<:deny:1156939743230173234> Changes requested"
+        }
+        PullRequestState::Open if approved => "<:accept:1156939740654878750> Approved",
+        PullRequestState::Open if !github_pull_request.requested_reviewers.is_empty() => {
+            "<:clock:1150889651914158111> Review requested"
+        }
+        PullRequestState::Open => "<:clock:1150889651914158111> Open",
+    };
+
+    let mut details = format!(
+        "[**{author}**](https://github.com/{author}) wants to merge into `{}`:\n[`#{} {}`]({})\n**Status:** {status}",
+        github_pull_request.base_branch,
+        github_pull_request.number,
+        github_pull_request.title,
+        github_pull_request.url,
+        author = github_pull_request.author,
+    );
+
+    if !github_pull_request.labels.is_empty() {
+        details.push_str(&format!(
+            "\n**Labels:** {}",
+            github_pull_request
+                .labels
+                .iter()
+                .map(|label| format!("`{label}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    details.push_str(&format!(
+        "\n-# +{} -{} in {} file{}",
+        github_pull_request.additions,
+        github_pull_request.deletions,
+        github_pull_request.changed_files,
+        if github_pull_request.changed_files == 1 {
+            ""
+        } else {
+            "s"
+        }
+    ));
+
+    let mut container_components = vec![CreateContainerComponent::Section(CreateSection::new(
+        vec![
+            CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                "## <:storage:1150889889294991381> Pull Request #{}",
+                github_pull_request.number
+            ))),
+            CreateSectionComponent::TextDisplay(CreateTextDisplay::new(details)),
+        ],
+        CreateSectionAccessory::Thumbnail(CreateThumbnail::new(CreateUnfurledMediaItem::new(
+            avatar_url.to_string(),
+        ))),
+    ))];
+
+    let mut reviewer_string = String::new();
+
+    for (reviewer, review) in &github_pull_request.reviews {
+        // a re-requested review is shown as pending below
+        if github_pull_request.requested_reviewers.contains(reviewer) {
+            continue;
+        }
+
+        reviewer_string.push_str(&match review.state {
+            ReviewState::Approved => format!(
+                "<:accept:1156939740654878750> **{reviewer}** approved <t:{}:R>\n",
+                review.submitted.timestamp()
+            ),
+            ReviewState::ChangesRequested => format!(
+                "<:deny:1156939743230173234> **{reviewer}** requested changes <t:{}:R>\n",
+                review.submitted.timestamp()
+            ),
+            _ => format!(
+                "<:storage:1150889889294991381> **{reviewer}** commented <t:{}:R>\n",
+                review.submitted.timestamp()
+            ),
+        });
+    }
+
+    for reviewer in &github_pull_request.requested_reviewers {
+        reviewer_string.push_str(&format!(
+            "<:clock:1150889651914158111> **{reviewer}** review requested\n"
+        ));
+    }
+
+    if !reviewer_string.is_empty() {
+        container_components.push(CreateContainerComponent::Separator(CreateSeparator::new()));
+        container_components.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!("### Reviews\n{reviewer_string}")),
+        ));
+    }
+
+    if !github_pull_request.workflow_status.is_empty() {
+        container_components.push(CreateContainerComponent::Separator(CreateSeparator::new()));
+        container_components.push(CreateContainerComponent::Section(CreateSection::new(
+            vec![
+                CreateSectionComponent::TextDisplay(CreateTextDisplay::new("### Workflow Status")),
+                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                    workflow_status_string(&github_pull_request.workflow_status),
+                )),
+            ],
+            CreateSectionAccessory::Button(
+                CreateButton::new_link(format!("{}/checks", github_pull_request.url))
+                    .label("View Checks"),
+            ),
+        )));
+    }
+
+    container_components
+}
+
+/// Whether a Discord request failed because what it points at no longer exists
+fn discord_not_found(err: &serenity::Error) -> bool {
+    matches!(
+        err,
+        serenity::Error::Http(serenity::all::HttpError::UnsuccessfulRequest(response))
+            if response.status_code == axum::http::StatusCode::NOT_FOUND
+    )
+}
+
+/// Edits a tracked message in place, a replay sends a new one instead if it was deleted.
+/// Tracked messages show titles, branches and commit messages, so nothing in them is allowed to ping.
+/// Returns `false` if the message is gone and the caller should send a new one.
+async fn edit_tracked_message(
+    state: &State,
+    delivery: &crate::models::GithubWebhookEvent,
+    channel_id: u64,
+    message_id: u64,
+    component: &CreateComponent<'_>,
+) -> Result<bool, anyhow::Error> {
+    let message = state
+        .bot
+        .read()
+        .await
+        .get_message(channel_id.into(), message_id.into())
+        .await;
+
+    let edited = match message {
+        Ok(mut message) => {
+            message
+                .edit(
+                    &*state.bot.read().await,
+                    serenity::all::EditMessage::new()
+                        .components(std::slice::from_ref(component))
+                        .flags(MessageFlags::IS_COMPONENTS_V2)
+                        .allowed_mentions(CreateAllowedMentions::new()),
+                )
+                .await
+        }
+        Err(err) => Err(err),
+    };
+
+    match edited {
+        Ok(_) => Ok(true),
+        Err(err) if delivery.replayed && discord_not_found(&err) => {
+            tracing::info!(
+                "tracked message {message_id} in {channel_id} is gone, sending a new one for the replay"
+            );
+
+            Ok(false)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Saves the pull request and renders it into its message, sending the message if there is none yet.
+/// Dry runs only send the message.
+async fn sync_pull_request_message(
+    state: &State,
+    delivery: &crate::models::GithubWebhookEvent,
+    github_pull_request: &mut crate::models::GithubPullRequest,
+    avatar_url: &str,
+) -> Result<(), anyhow::Error> {
+    let component = CreateComponent::Container(CreateContainer::new(pull_request_components(
+        github_pull_request,
+        avatar_url,
+    )));
+
+    if let Some(message_id) = github_pull_request.message_id
+        && edit_tracked_message(
+            state,
+            delivery,
+            github_pull_request.channel_id as u64,
+            message_id as u64,
+            &component,
+        )
+        .await?
+    {
+        return crate::models::update_github_pull_request(
+            state.database.write(),
+            github_pull_request,
+        )
+        .await;
+    }
+
+    let message = GenericChannelId::new(github_pull_request.channel_id as u64)
+        .send_message(
+            &*state.bot.read().await,
+            CreateMessage::new()
+                .components(&[component])
+                .flags(MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+
+    if delivery.replay_channel_id.is_some() {
+        return Ok(());
+    }
+
+    // saved before the thread is opened, so a retry edits this message instead of posting another
+    github_pull_request.message_id = Some(message.id.get() as i64);
+    crate::models::update_github_pull_request(state.database.write(), github_pull_request).await?;
+
+    // a replaced message keeps the thread it already has
+    if state.env.github_threads
+        && github_pull_request.thread_id.is_none()
+        && let Some(thread_id) = create_discussion_thread(
+            state,
+            github_pull_request.channel_id as u64,
+            message.id.get(),
+            &format!(
+                "#{} {}",
+                github_pull_request.number, github_pull_request.title
+            ),
+        )
+        .await
+    {
+        github_pull_request.thread_id = Some(thread_id as i64);
+        crate::models::set_github_pull_request_thread(
+            state.database.write(),
+            github_pull_request.id,
+            thread_id,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Opens the thread issue and pull request activity is mirrored into.
+/// Failures are only logged, the activity then goes next to the message instead.
+async fn create_discussion_thread(
+    state: &State,
+    channel_id: u64,
+    message_id: u64,
+    name: &str,
+) -> Option<u64> {
+    match ChannelId::new(channel_id)
+        .create_thread_from_message(
+            &*state.bot.read().await,
+            message_id.into(),
+            CreateThread::new(crate::utils::truncate_text(name, 100)),
+        )
+        .await
+    {
+        Ok(thread) => Some(thread.id.get()),
+        Err(err) => {
+            tracing::warn!(
+                "failed to create thread for message {message_id} in {channel_id}: {:?}",
+                err
+            );
+
+            None
+        }
+    }
+}
+
+async fn set_thread_archived(
+    state: &State,
+    thread_id: u64,
+    archived: bool,
+) -> Result<(), anyhow::Error> {
+    ThreadId::new(thread_id)
+        .edit(
+            &*state.bot.read().await,
+            EditThread::new().archived(archived),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Appends a line of issue or pull request activity to its thread
+async fn send_thread_activity(
+    state: &State,
+    thread_id: u64,
+    activity: &str,
+) -> Result<(), anyhow::Error> {
+    GenericChannelId::new(thread_id)
+        .send_message(
+            &*state.bot.read().await,
+            CreateMessage::new()
+                .components(&[CreateComponent::TextDisplay(CreateTextDisplay::new(
+                    crate::utils::neutralize_mentions(activity),
+                ))])
+                .flags(MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Review and comment notifications for a pull request go to its thread,
+/// or next to its message if threads are disabled
+async fn pull_request_discussion_channel(
+    state: &State,
+    delivery: &crate::models::GithubWebhookEvent,
+    github_pull_request: &mut crate::models::GithubPullRequest,
+) -> Result<u64, anyhow::Error> {
+    if let Some(replay_channel_id) = delivery.replay_channel_id {
+        return Ok(replay_channel_id);
+    }
+
+    if let Some(thread_id) = github_pull_request.thread_id {
+        return Ok(thread_id as u64);
+    }
+
+    if state.env.github_threads
+        && let Some(message_id) = github_pull_request.message_id
+        && let Some(thread_id) = create_discussion_thread(
+            state,
+            github_pull_request.channel_id as u64,
+            message_id as u64,
+            &format!(
+                "#{} {}",
+                github_pull_request.number, github_pull_request.title
+            ),
+        )
+        .await
+    {
+        github_pull_request.thread_id = Some(thread_id as i64);
+        crate::models::set_github_pull_request_thread(
+            state.database.write(),
+            github_pull_request.id,
+            thread_id,
+        )
+        .await?;
+
+        return Ok(thread_id);
+    }
+
+    Ok(github_pull_request.channel_id as u64)
+}
+
+fn pull_request_activity(
+    pull_request: &octocrab::models::webhook_events::payload::PullRequestWebhookEventPayload,
+    sender: &octocrab::models::Author,
+) -> Option<String> {
+    let actor = format!("[**{}**]({})", sender.login, sender.html_url);
+
+    match pull_request.action {
+        PullRequestWebhookEventAction::Closed if pull_request.pull_request.merged_at.is_some() => {
+            Some(format!(
+                "<:accept:1156939740654878750> {actor} merged the pull request."
+            ))
+        }
+        PullRequestWebhookEventAction::Closed => Some(format!(
+            "<:deny:1156939743230173234> {actor} closed the pull request."
+        )),
+        PullRequestWebhookEventAction::Reopened => Some(format!(
+            "<:storage:1150889889294991381> {actor} reopened the pull request."
+        )),
+        PullRequestWebhookEventAction::ConvertedToDraft => Some(format!(
+            "<:clock:1150889651914158111> {actor} converted the pull request to a draft."
+        )),
+        PullRequestWebhookEventAction::ReadyForReview => Some(format!(
+            "<:storage:1150889889294991381> {actor} marked the pull request as ready for review."
+        )),
+        PullRequestWebhookEventAction::ReviewRequested => pull_request
+            .requested_reviewer
+            .as_ref()
+            .map(|reviewer| reviewer.login.clone())
+            .or_else(|| {
+                pull_request
+                    .requested_team
+                    .as_ref()
+                    .map(|team| team.name.clone())
+            })
+            .map(|reviewer| format!("{actor} requested a review from **{reviewer}**.")),
+        PullRequestWebhookEventAction::ReviewRequestRemoved => pull_request
+            .requested_reviewer
+            .as_ref()
+            .map(|reviewer| reviewer.login.clone())
+            .or_else(|| {
+                pull_request
+                    .requested_team
+                    .as_ref()
+                    .map(|team| team.name.clone())
+            })
+            .map(|reviewer| format!("{actor} removed the review request for **{reviewer}**.")),
+        PullRequestWebhookEventAction::Labeled => pull_request
+            .label
+            .as_ref()
+            .map(|label| format!("{actor} added the label `{}`.", label.name)),
+        PullRequestWebhookEventAction::Unlabeled => pull_request
+            .label
+            .as_ref()
+            .map(|label| format!("{actor} removed the label `{}`.", label.name)),
+        PullRequestWebhookEventAction::Assigned => pull_request
+            .assignee
+            .as_ref()
+            .map(|assignee| format!("{actor} assigned **{}**.", assignee.login)),
+        PullRequestWebhookEventAction::Unassigned => pull_request
+            .assignee
+            .as_ref()
+            .map(|assignee| format!("{actor} unassigned **{}**.", assignee.login)),
+        PullRequestWebhookEventAction::Synchronize => pull_request.after.as_ref().map(|after| {
+            format!(
+                "{actor} pushed [`{}`]({}/commits/{after}).",
+                after.chars().take(7).collect::<String>(),
+                pull_request
+                    .pull_request
+                    .html_url
+                    .as_ref()
+                    .map_or_else(|| "".to_string(), |url| url.to_string())
+            )
+        }),
+        _ => None,
+    }
+}
+
+fn issue_activity(
+    issue: &octocrab::models::webhook_events::payload::IssuesWebhookEventPayload,
+    sender: &octocrab::models::Author,
+) -> Option<String> {
+    let actor = format!("[**{}**]({})", sender.login, sender.html_url);
+
+    match issue.action {
+        IssuesWebhookEventAction::Closed => Some(format!(
+            "<:deny:1156939743230173234> {actor} closed the issue{}.",
+            match issue.issue.state_reason {
+                Some(IssueStateReason::NotPlanned) => " as not planned",
+                Some(IssueStateReason::Completed) => " as completed",
+                _ => "",
+            }
+        )),
+        IssuesWebhookEventAction::Reopened => Some(format!(
+            "<:hammer:1150889684227076227> {actor} reopened the issue."
+        )),
+        IssuesWebhookEventAction::Edited => issue
+            .changes
+            .as_ref()
+            .and_then(|changes| changes.title.as_ref())
+            .map(|title| {
+                format!(
+                    "{actor} renamed the issue from `{}` to `{}`.",
+                    title.from, issue.issue.title
+                )
+            }),
+        IssuesWebhookEventAction::Labeled => issue
+            .label
+            .as_ref()
+            .map(|label| format!("{actor} added the label `{}`.", label.name)),
+        IssuesWebhookEventAction::Unlabeled => issue
+            .label
+            .as_ref()
+            .map(|label| format!("{actor} removed the label `{}`.", label.name)),
+        IssuesWebhookEventAction::Assigned => issue
+            .assignee
+            .as_ref()
+            .map(|assignee| format!("{actor} assigned **{}**.", assignee.login)),
+        IssuesWebhookEventAction::Unassigned => issue
+            .assignee
+            .as_ref()
+            .map(|assignee| format!("{actor} unassigned **{}**.", assignee.login)),
+        IssuesWebhookEventAction::Milestoned => issue
+            .milestone
+            .as_ref()
+            .map(|milestone| format!("{actor} added the issue to `{}`.", milestone.title)),
+        IssuesWebhookEventAction::Demilestoned => issue
+            .milestone
+            .as_ref()
+            .map(|milestone| format!("{actor} removed the issue from `{}`.", milestone.title)),
+        _ => None,
+    }
+}
+
+fn discussion_components(
+    title: String,
+    description: String,
+    body: Option<&str>,
+    avatar_url: &str,
+    repository_url: &str,
+) -> Vec<CreateContainerComponent<'static>> {
+    let mut container_components = vec![CreateContainerComponent::Section(CreateSection::new(
+        vec![
+            CreateSectionComponent::TextDisplay(CreateTextDisplay::new(title)),
+            CreateSectionComponent::TextDisplay(CreateTextDisplay::new(description)),
+        ],
+        CreateSectionAccessory::Thumbnail(CreateThumbnail::new(CreateUnfurledMediaItem::new(
+            avatar_url.to_string(),
+        ))),
+    ))];
+
+    if let Some(body) = body.map(str::trim).filter(|body| !body.is_empty()) {
+        // text displays share a 4000 character budget per message
+        let excerpt = crate::utils::truncate_text(&crate::utils::neutralize_mentions(body), 1500)
+            .lines()
+            .map(|line| format!("> {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        container_components.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(excerpt),
+        ));
+    }
+
+    container_components.push(CreateContainerComponent::TextDisplay(
+        CreateTextDisplay::new(format!("-# {repository_url}")),
+    ));
+
+    container_components
+}
+
+#[derive(Deserialize)]
+struct DiscussionCategory {
+    name: String,
+    #[serde(default)]
+    is_answerable: bool,
+}
+
+#[derive(Deserialize)]
+struct DiscussionData {
+    number: i64,
+    title: String,
+    html_url: String,
+    body: Option<String>,
+    category: DiscussionCategory,
+}
+
+/// Opens a post in a forum channel, applying the forum tag named like `tag` if there is one
+async fn create_forum_post(
+    state: &State,
+    channel_id: u64,
+    name: &str,
+    tag: &str,
+    container_components: Vec<CreateContainerComponent<'static>>,
+) -> Result<u64, anyhow::Error> {
+    let http = state.bot.read().await;
+
+    let Some(forum) = http.get_channel(channel_id.into()).await?.guild() else {
+        return Err(anyhow::anyhow!(
+            "github forum channel ID {} is not a guild channel",
+            channel_id
+        ));
+    };
+
+    let component = CreateComponent::Container(CreateContainer::new(container_components));
+    let mut forum_post = CreateForumPost::new(
+        crate::utils::truncate_text(name, 100),
+        CreateMessage::new()
+            .components(vec![component])
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .allowed_mentions(CreateAllowedMentions::new()),
+    );
+
+    if let Some(forum_tag) = forum
+        .available_tags
+        .iter()
+        .find(|forum_tag| forum_tag.name.eq_ignore_ascii_case(tag))
+    {
+        forum_post = forum_post.add_applied_tag(forum_tag.id);
+    }
+
+    let thread = forum.id.create_forum_post(&*http, forum_post).await?;
+
+    Ok(thread.id.get())
+}
+
+/// Comment bodies are user content, so nothing in them is allowed to ping
+async fn send_discussion_message(
+    state: &State,
+    channel_id: u64,
+    container_components: Vec<CreateContainerComponent<'static>>,
+) -> Result<(), anyhow::Error> {
+    let component = CreateComponent::Container(CreateContainer::new(container_components));
+
+    GenericChannelId::new(channel_id)
+        .send_message(
+            &*state.bot.read().await,
+            CreateMessage::new()
+                .components(&[component])
+                .flags(MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// The branch an event applies to, used for routing
+fn event_branch(specific: &WebhookEventPayload) -> Option<String> {
+    match specific {
+        WebhookEventPayload::Push(push) => push
+            .r#ref
+            .strip_prefix("refs/heads/")
+            .map(|branch| branch.to_string()),
+        WebhookEventPayload::PullRequest(pull_request) => {
+            Some(pull_request.pull_request.base.ref_field.clone())
+        }
+        WebhookEventPayload::PullRequestReviewComment(review_comment) => {
+            Some(review_comment.pull_request.base.ref_field.clone())
+        }
+        WebhookEventPayload::Create(create) if create.ref_type == RefType::Branch => {
+            Some(create.r#ref.clone())
+        }
+        WebhookEventPayload::Delete(delete) if delete.ref_type == RefType::Branch => {
+            Some(delete.r#ref.clone())
+        }
+        WebhookEventPayload::Deployment(deployment) => deployment
+            .deployment
+            .get("ref")
+            .and_then(|branch| branch.as_str())
+            .map(|branch| branch.to_string()),
+        WebhookEventPayload::DeploymentStatus(deployment_status) => deployment_status
+            .deployment
+            .get("ref")
+            .and_then(|branch| branch.as_str())
+            .map(|branch| branch.to_string()),
+        WebhookEventPayload::WorkflowRun(workflow_run) => workflow_run
+            .workflow_run
+            .get("head_branch")
+            .and_then(|branch| branch.as_str())
+            .map(|branch| branch.to_string()),
+        WebhookEventPayload::WorkflowJob(workflow_job) => workflow_job
+            .workflow_job
+            .get("head_branch")
+            .and_then(|branch| branch.as_str())
+            .map(|branch| branch.to_string()),
+        _ => None,
+    }
+}
+
+/// Creating or deleting a branch or tag is a push too, so it goes through the same push filters
+async fn reference_event_allowed(
+    state: &State,
+    event: &octocrab::models::webhook_events::WebhookEvent,
+) -> Result<bool, anyhow::Error> {
+    let (ref_type, r#ref) = match &event.specific {
+        WebhookEventPayload::Create(create) => (&create.ref_type, &create.r#ref),
+        WebhookEventPayload::Delete(delete) => (&delete.ref_type, &delete.r#ref),
+        _ => return Ok(true),
+    };
+    let Some(repository) = &event.repository else {
+        return Ok(true);
+    };
+
+    let repository_name = repository
+        .full_name
+        .clone()
+        .unwrap_or_else(|| repository.name.clone());
+    let sender_is_bot = event
+        .sender
+        .as_ref()
+        .is_some_and(|sender| sender.r#type == "Bot" || sender.login.ends_with("[bot]"));
+
+    crate::models::github_push_allowed(
+        state.database.read(),
+        &repository_name,
+        (*ref_type == RefType::Branch).then_some(r#ref.as_str()),
+        sender_is_bot,
+    )
+    .await
+}
+
+async fn handle_repository_event(
+    state: &State,
+    delivery: &crate::models::GithubWebhookEvent,
+    event: octocrab::models::webhook_events::WebhookEvent,
+) -> Result<(), anyhow::Error> {
+    let Some(organization) = event.organization else {
+        return Err(anyhow::anyhow!(
+            "missing organization information in webhook payload"
+        ));
+    };
+    let Some(repository) = event.repository else {
+        return Err(anyhow::anyhow!(
+            "missing repository information in webhook payload"
+        ));
+    };
+    let Some(sender) = event.sender else {
+        return Err(anyhow::anyhow!(
+            "missing sender information in webhook payload"
+        ));
+    };
+
+    let repository_name = repository
+        .full_name
+        .clone()
+        .unwrap_or_else(|| repository.name.clone());
+    let repository_url = repository
+        .html_url
+        .as_ref()
+        .map_or_else(|| repository.name.clone(), |h| h.to_string());
+
+    let mut container_components = Vec::new();
+    let mut edit_github_message = None;
+    let mut create_github_message = None;
+    let mut track_github_issue = None;
+    let mut reached_star_milestone = None;
+    let route_branch = event_branch(&event.specific);
+    let sender_is_bot = sender.r#type == "Bot" || sender.login.ends_with("[bot]");
+
+    match event.specific {
+        WebhookEventPayload::Push(push) if push.deleted => {
+            return Ok(());
+        }
+        WebhookEventPayload::Push(push) if push.created && push.r#ref.starts_with("refs/tags/") => {
+            // new tags are announced through the create event
+            return Ok(());
+        }
+        WebhookEventPayload::Push(push)
+            if push.created
+                && push.commits.is_empty()
+                && state
+                    .renderers
+                    .renderer(&octocrab::models::webhook_events::WebhookEventType::Create)
+                    .is_some() =>
+        {
+            // a new branch without new commits is announced through the create event
+            return Ok(());
+        }
+        WebhookEventPayload::Push(push) if push.r#ref.starts_with("refs/tags/") => {
+            if !crate::models::github_push_allowed(
+                state.database.read(),
+                &repository_name,
+                None,
+                sender_is_bot,
+            )
+            .await?
+            {
+                return Ok(());
+            }
+
+            let tag = push.r#ref.trim_start_matches("refs/tags/");
+
+            let mut tag_string = format!(
+                "[**{}**]({}) pushed the tag [`{tag}`]({}/tree/{tag})",
+                sender.login,
+                sender.html_url,
+                repository
+                    .html_url
+                    .as_ref()
+                    .map_or_else(|| "".to_string(), |h| h.to_string()),
+            );
+
+            if let Some(head_commit) = &push.head_commit {
+                tag_string.push_str(&format!(
+                    " at [`{}`]({}): {}",
+                    head_commit.id.chars().take(7).collect::<String>(),
+                    head_commit.url,
+                    head_commit.message.lines().next().unwrap_or_default()
+                ));
+            }
+
+            container_components.push(CreateContainerComponent::Section(CreateSection::new(
+                vec![
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                        "## <:package:1150890021516234832> Tag `{tag}` pushed"
+                    ))),
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(tag_string)),
+                ],
+                CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
+                    CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
+                )),
+            )));
+        }
+        WebhookEventPayload::Push(push) => {
+            let branch = push
+                .r#ref
+                .strip_prefix("refs/heads/")
+                .unwrap_or(&push.r#ref)
+                .to_string();
+
+            if !crate::models::github_push_allowed(
+                state.database.read(),
+                &repository_name,
+                Some(&branch),
+                sender_is_bot,
+            )
+            .await?
+            {
+                return Ok(());
+            }
+
+            // dry runs always send the push on its own
+            let coalesced_github_message = if state.env.github_push_coalesce_seconds > 0
+                && delivery.replay_channel_id.is_none()
+            {
+                crate::models::recent_github_push_message(
+                    state.database.read(),
+                    *repository.id as i64,
+                    &branch,
+                    chrono::Duration::seconds(state.env.github_push_coalesce_seconds as i64),
+                )
+                .await?
+            } else {
+                None
+            };
+
+            if let Some(mut github_message) = coalesced_github_message {
+                // a force push rewrote the branch, the commits listed so far may no longer exist
+                if push.forced {
+                    github_message.commits.clear();
+                }
+
+                for commit in push.commits {
+                    if !github_message.commits.iter().any(|c| c.id == commit.id) {
+                        github_message.commits.push(commit);
+                    }
+                }
+                github_message.forced |= push.forced;
+
+                // workflows of the earlier heads no longer describe the branch
+                github_message.workflow_sha = push.after.clone();
+                github_message.workflow_status.clear();
+
+                crate::models::update_github_push_message(state.database.write(), &github_message)
+                    .await?;
+
+                if crate::models::requeue_deferred_github_webhook_events(
+                    state.database.write(),
+                    *repository.id as i64,
+                    &push.after,
+                )
+                .await?
+                    > 0
+                {
+                    GITHUB_EVENTS_NOTIFY.notify_one();
+                }
+
+                let compare_url = match &github_message.before_sha {
+                    Some(before_sha) => format!(
+                        "{repository_url}/compare/{}...{}",
+                        before_sha.chars().take(12).collect::<String>(),
+                        push.after.chars().take(12).collect::<String>()
+                    ),
+                    None => push.compare.to_string(),
+                };
+
+                edit_github_message = Some((github_message, compare_url, "Compare"));
+            } else {
+                let mut commit_string = String::new();
+
+                for commit in push.commits.iter().take(8) {
+                    commit_string.push_str(&format!(
+                        "[`{}`]({}) ({}): {}\n",
+                        commit.id.chars().take(7).collect::<String>(),
+                        commit.url,
+                        commit.author.user.name,
+                        commit.message.lines().next().unwrap_or_default()
+                    ));
+                }
+
+                if push.commits.len() > 8 {
+                    commit_string
+                        .push_str(&format!("-# ... {} more Commits\n", push.commits.len() - 8));
+                }
+
+                container_components.push(CreateContainerComponent::Section(CreateSection::new(
+                    vec![
+                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                            "## <:package:1150890021516234832> {} Commit{} pushed to `{}`",
+                            push.commits.len(),
+                            if push.commits.len() == 1 { "" } else { "s" },
+                            branch
+                        ))),
+                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(commit_string)),
+                    ],
+                    CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
+                        CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
+                    )),
+                )));
+
+                if let Some(head_commit) = push.head_commit {
+                    // new branches start from the zero sha
+                    let before_sha =
+                        (!push.before.chars().all(|c| c == '0')).then_some(push.before);
+
+                    create_github_message =
+                        Some((head_commit.id, push.commits, branch, None, before_sha));
+                }
+            }
+        }
+        WebhookEventPayload::Star(star) => match star.action {
+            StarWebhookEventAction::Created => {
+                pub static STAR_DEDUP_CACHE: LazyLock<moka::future::Cache<(u64, u64), ()>> =
+                    LazyLock::new(|| {
+                        moka::future::Cache::builder()
+                            .time_to_live(std::time::Duration::from_secs(600))
+                            .max_capacity(1000)
+                            .build()
+                    });
+
+                // dry runs show the star itself, counting it again would skew digests and milestones
+                if delivery.replay_channel_id.is_none() {
+                    let dedup_key = (*repository.id, *sender.id);
+                    let repository_stars = if STAR_DEDUP_CACHE.get(&dedup_key).await.is_some() {
+                        if !count_stars_silently(state) {
+                            return Ok(());
+                        }
+
+                        // the star is already counted, but a retry still owes a milestone whose
+                        // announcement failed to send
+                        let Some(repository_stars) = crate::models::get_github_repository_stars(
+                            state.database.read(),
+                            *repository.id as i64,
+                        )
+                        .await?
+                        else {
+                            return Ok(());
+                        };
+
+                        repository_stars
+                    } else {
+                        STAR_DEDUP_CACHE.insert(dedup_key, ()).await;
+
+                        crate::models::record_github_star(
+                            state.database.write(),
+                            *repository.id as i64,
+                            &repository_name,
+                            delivery.source.as_deref(),
+                            repository.stargazers_count.unwrap_or(0) as i64,
+                            true,
+                            &state.env.github_star_milestones,
+                        )
+                        .await?
+                    };
+
+                    if count_stars_silently(state) {
+                        let Some(milestone) =
+                            repository_stars.reached_milestone(&state.env.github_star_milestones)
+                        else {
+                            return Ok(());
+                        };
+
+                        // only saved once the announcement is sent, so a failed send is retried
+                        reached_star_milestone = Some((repository_stars.id, milestone));
+                    }
+                }
+
+                if let Some((_, milestone)) = reached_star_milestone {
+                    container_components.push(CreateContainerComponent::Section(
+                        CreateSection::new(
+                            vec![
+                                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                                    format!(
+                                        "## <:star:1229766059381358623> {milestone} stars reached"
+                                    ),
+                                )),
+                                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                                    format!(
+                                        "`{repository_name}` just reached `{milestone}` stars, the latest one from [**{}**]({})!",
+                                        sender.login, sender.html_url,
+                                    ),
+                                )),
+                            ],
+                            CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
+                                CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
+                            )),
+                        ),
+                    ));
+                } else {
+                    container_components.push(CreateContainerComponent::Section(
+                        CreateSection::new(
+                            vec![
+                                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                                    "## <:star:1229766059381358623> Repository starred",
+                                )),
+                                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                                    format!(
+                                        "[**{}**]({}) starred the repository!",
+                                        sender.login, sender.html_url,
+                                    ),
+                                )),
+                                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                                    format!(
+                                        "The new star count is `{}`.",
+                                        repository.stargazers_count.unwrap_or(0)
+                                    ),
+                                )),
+                            ],
+                            CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
+                                CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
+                            )),
+                        ),
+                    ));
+                }
+            }
+            StarWebhookEventAction::Deleted => {
+                pub static UNSTAR_DEDUP_CACHE: LazyLock<moka::future::Cache<(u64, u64), ()>> =
+                    LazyLock::new(|| {
+                        moka::future::Cache::builder()
+                            .time_to_live(std::time::Duration::from_secs(600))
+                            .max_capacity(1000)
+                            .build()
+                    });
+
+                if delivery.replay_channel_id.is_none() {
+                    let dedup_key = (*repository.id, *sender.id);
+                    if UNSTAR_DEDUP_CACHE.get(&dedup_key).await.is_some() {
+                        return Ok(());
+                    }
+
+                    UNSTAR_DEDUP_CACHE.insert(dedup_key, ()).await;
+
+                    crate::models::record_github_star(
+                        state.database.write(),
+                        *repository.id as i64,
+                        &repository_name,
+                        delivery.source.as_deref(),
+                        repository.stargazers_count.unwrap_or(0) as i64,
+                        false,
+                        &state.env.github_star_milestones,
+                    )
+                    .await?;
+
+                    if count_stars_silently(state) {
+                        return Ok(());
+                    }
+                }
+
+                container_components.push(CreateContainerComponent::Section(CreateSection::new(
+                    vec![
+                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                            "## <:star:1229766059381358623> Repository unstarred",
+                        )),
+                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                            "[**{}**]({}) unstarred the repository!",
+                            sender.login, sender.html_url,
+                        ))),
+                        CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                            "The new star count is `{}`.",
+                            repository.stargazers_count.unwrap_or(0)
+                        ))),
+                    ],
+                    CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
+                        CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
+                    )),
+                )));
+            }
+            _ => {}
+        },
+        WebhookEventPayload::PullRequest(pull_request) => {
+            match pull_request.action {
+                PullRequestWebhookEventAction::Opened
+                | PullRequestWebhookEventAction::Reopened
+                | PullRequestWebhookEventAction::Closed
+                | PullRequestWebhookEventAction::ConvertedToDraft
+                | PullRequestWebhookEventAction::ReadyForReview
+                | PullRequestWebhookEventAction::ReviewRequested
+                | PullRequestWebhookEventAction::ReviewRequestRemoved
+                | PullRequestWebhookEventAction::Labeled
+                | PullRequestWebhookEventAction::Unlabeled
+                | PullRequestWebhookEventAction::Assigned
+                | PullRequestWebhookEventAction::Unassigned
+                | PullRequestWebhookEventAction::Synchronize
+                | PullRequestWebhookEventAction::Edited => {}
+                _ => return Ok(()),
+            }
+
+            let mut github_pull_request = load_github_pull_request(
+                state,
+                delivery,
+                &repository_name,
+                *repository.id as i64,
+                &pull_request.pull_request,
+            )
+            .await?;
+            github_pull_request.apply_pull_request(&pull_request.pull_request);
+
+            sync_pull_request_message(
+                state,
+                delivery,
+                &mut github_pull_request,
+                &organization.avatar_url.to_string(),
+            )
+            .await?;
+
+            if let Some(thread_id) = github_pull_request.thread_id
+                && let Some(activity) = pull_request_activity(&pull_request, &sender)
+            {
+                let thread_id = thread_id as u64;
+
+                if pull_request.action == PullRequestWebhookEventAction::Reopened {
+                    set_thread_archived(state, thread_id, false).await?;
+                }
+
+                send_thread_activity(state, thread_id, &activity).await?;
+
+                if pull_request.action == PullRequestWebhookEventAction::Closed {
+                    set_thread_archived(state, thread_id, true).await?;
+                }
+            }
+
+            return Ok(());
+        }
+        WebhookEventPayload::PullRequestReview(review) => {
+            match review.action {
+                PullRequestReviewWebhookEventAction::Submitted
+                | PullRequestReviewWebhookEventAction::Dismissed => {}
+                _ => return Ok(()),
+            }
+
+            let mut github_pull_request = load_github_pull_request(
+                state,
+                delivery,
+                &repository_name,
+                *repository.id as i64,
+                &review.pull_request,
+            )
+            .await?;
+            github_pull_request.apply_pull_request(&review.pull_request);
+
+            if let Some(reviewer) = &review.review.user
+                && let Some(review_state) = review.review.state
+            {
+                github_pull_request.record_review(
+                    &reviewer.login,
+                    if review.action == PullRequestReviewWebhookEventAction::Dismissed {
+                        ReviewState::Dismissed
+                    } else {
+                        review_state
+                    },
+                );
+            }
+
+            sync_pull_request_message(
+                state,
+                delivery,
+                &mut github_pull_request,
+                &organization.avatar_url.to_string(),
+            )
+            .await?;
+
+            if review.action != PullRequestReviewWebhookEventAction::Submitted {
+                return Ok(());
+            }
+
+            let body = review
+                .review
+                .body
+                .as_deref()
+                .filter(|body| !body.trim().is_empty());
+            let (emoji, verb) = match review.review.state {
+                Some(ReviewState::Approved) => ("<:accept:1156939740654878750>", "approved"),
+                Some(ReviewState::ChangesRequested) => {
+                    ("<:deny:1156939743230173234>", "requested changes on")
+                }
+                Some(ReviewState::Commented) if body.is_some() => {
+                    ("<:storage:1150889889294991381>", "reviewed")
+                }
+                _ => return Ok(()),
+            };
+
+            let channel_id =
+                pull_request_discussion_channel(state, delivery, &mut github_pull_request).await?;
+
+            send_discussion_message(
+                state,
+                channel_id,
+                discussion_components(
+                    format!("## {emoji} Pull Request review"),
+                    format!(
+                        "[**{}**]({}) {verb} [`#{} {}`]({}).",
+                        sender.login,
+                        sender.html_url,
+                        github_pull_request.number,
+                        github_pull_request.title,
+                        review.review.html_url
+                    ),
+                    body,
+                    &organization.avatar_url.to_string(),
+                    &repository_url,
+                ),
+            )
+            .await?;
+
+            return Ok(());
+        }
+        WebhookEventPayload::PullRequestReviewComment(review_comment) => {
+            if review_comment.action != PullRequestReviewCommentWebhookEventAction::Created {
+                return Ok(());
+            }
+
+            let channel_id = match crate::models::get_github_pull_request(
+                state.database.read(),
+                *repository.id as i64,
+                review_comment.pull_request.number as i64,
+            )
+            .await?
+            {
+                Some(mut github_pull_request) => {
+                    pull_request_discussion_channel(state, delivery, &mut github_pull_request)
+                        .await?
+                }
+                None => {
+                    route_channel_id(
+                        state,
+                        delivery,
+                        &repository_name,
+                        &delivery.event,
+                        route_branch.as_deref(),
+                    )
+                    .await?
+                }
+            };
+
+            let comment = review_comment.comment;
+
+            send_discussion_message(
+                state,
+                channel_id,
+                discussion_components(
+                    "## <:storage:1150889889294991381> Review comment".to_string(),
+                    format!(
+                        "[**{}**]({}) commented on [`{}`]({}) in [`#{} {}`]({}).",
+                        sender.login,
+                        sender.html_url,
+                        comment.path,
+                        comment.html_url,
+                        review_comment.pull_request.number,
+                        review_comment.pull_request.title.unwrap_or_default(),
+                        review_comment
+                            .pull_request
+                            .html_url
+                            .map_or_else(|| "".to_string(), |url| url.to_string()),
+                    ),
+                    Some(comment.body.as_str()),
+                    &organization.avatar_url.to_string(),
+                    &repository_url,
+                ),
+            )
+            .await?;
+
+            return Ok(());
+        }
+        WebhookEventPayload::IssueComment(issue_comment) => {
+            if issue_comment.action != IssueCommentWebhookEventAction::Created {
+                return Ok(());
+            }
+
+            let github_pull_request = if issue_comment.issue.pull_request.is_some() {
+                crate::models::get_github_pull_request(
+                    state.database.read(),
+                    *repository.id as i64,
+                    issue_comment.issue.number as i64,
+                )
+                .await?
+            } else {
+                None
+            };
+            let github_issue = if issue_comment.issue.pull_request.is_none() {
+                crate::models::get_github_issue(
+                    state.database.read(),
+                    *repository.id as i64,
+                    issue_comment.issue.number as i64,
+                )
+                .await?
+            } else {
+                None
+            };
+
+            let channel_id = match (github_pull_request, github_issue) {
+                (Some(mut github_pull_request), _) => {
+                    pull_request_discussion_channel(state, delivery, &mut github_pull_request)
+                        .await?
+                }
+                (
+                    None,
+                    Some(crate::models::GithubIssue {
+                        thread_id: Some(thread_id),
+                        ..
+                    }),
+                ) if delivery.replay_channel_id.is_none() => thread_id as u64,
+                _ => {
+                    route_channel_id(state, delivery, &repository_name, &delivery.event, None)
+                        .await?
+                }
+            };
+
+            send_discussion_message(
+                state,
+                channel_id,
+                discussion_components(
+                    if issue_comment.issue.pull_request.is_some() {
+                        "## <:storage:1150889889294991381> Pull Request comment".to_string()
+                    } else {
+                        "## <:hammer:1150889684227076227> Issue comment".to_string()
+                    },
+                    format!(
+                        "[**{}**]({}) commented on [`#{} {}`]({}).",
+                        sender.login,
+                        sender.html_url,
+                        issue_comment.issue.number,
+                        issue_comment.issue.title,
+                        issue_comment.comment.html_url
+                    ),
+                    issue_comment.comment.body.as_deref(),
+                    &organization.avatar_url.to_string(),
+                    &repository_url,
+                ),
+            )
+            .await?;
+
+            return Ok(());
+        }
+        WebhookEventPayload::Discussion(discussion) => {
+            #[derive(Deserialize)]
+            struct AnswerUser {
+                login: String,
+            }
+
+            #[derive(Deserialize)]
+            struct AnswerData {
+                html_url: String,
+                body: Option<String>,
+                user: AnswerUser,
+            }
+
+            #[derive(Deserialize)]
+            struct CategoryFrom {
+                from: DiscussionCategory,
+            }
+
+            #[derive(Deserialize)]
+            struct CategoryChanges {
+                category: CategoryFrom,
+            }
+
+            let discussion_data: DiscussionData = serde_json::from_value(discussion.discussion)?;
+            let discussion_link = format!(
+                "[`#{} {}`]({})",
+                discussion_data.number, discussion_data.title, discussion_data.html_url
+            );
+            let github_discussion = crate::models::get_github_discussion(
+                state.database.read(),
+                *repository.id as i64,
+                discussion_data.number,
+            )
+            .await?;
+
+            let (title, description, body) = match discussion.action {
+                DiscussionWebhookEventAction::Created => (
+                    "Discussion opened",
+                    format!(
+                        "[**{}**]({}) opened {discussion_link} in **{}**.",
+                        sender.login, sender.html_url, discussion_data.category.name
+                    ),
+                    discussion_data.body.clone(),
+                ),
+                DiscussionWebhookEventAction::Answered => {
+                    let answer = discussion
+                        .answer
+                        .map(serde_json::from_value::<AnswerData>)
+                        .transpose()?;
+
+                    match answer {
+                        Some(answer) => (
+                            "Discussion answered",
+                            format!(
+                                "[**{}**]({}) marked [an answer by **{}**]({}) on {discussion_link}.",
+                                sender.login, sender.html_url, answer.user.login, answer.html_url
+                            ),
+                            answer.body,
+                        ),
+                        None => (
+                            "Discussion answered",
+                            format!(
+                                "[**{}**]({}) marked an answer on {discussion_link}.",
+                                sender.login, sender.html_url
+                            ),
+                            None,
+                        ),
+                    }
+                }
+                DiscussionWebhookEventAction::CategoryChanged => {
+                    let changes = discussion
+                        .changes
+                        .map(serde_json::from_value::<CategoryChanges>)
+                        .transpose()?;
+
+                    (
+                        "Discussion moved",
+                        format!(
+                            "[**{}**]({}) moved {discussion_link} from **{}** to **{}**.",
+                            sender.login,
+                            sender.html_url,
+                            changes.map_or_else(
+                                || "another category".to_string(),
+                                |changes| changes.category.from.name
+                            ),
+                            discussion_data.category.name
+                        ),
+                        None,
+                    )
+                }
+                DiscussionWebhookEventAction::Locked => (
+                    "Discussion locked",
+                    format!(
+                        "[**{}**]({}) locked {discussion_link}.",
+                        sender.login, sender.html_url
+                    ),
+                    None,
+                ),
+                _ => return Ok(()),
+            };
+
+            if let Some(github_discussion) = &github_discussion
+                && delivery.replay_channel_id.is_none()
+            {
+                send_thread_activity(state, github_discussion.thread_id as u64, &description)
+                    .await?;
+
+                if discussion.action == DiscussionWebhookEventAction::Locked {
+                    set_thread_archived(state, github_discussion.thread_id as u64, true).await?;
+                }
+            }
+
+            let channel_id =
+                route_channel_id(state, delivery, &repository_name, &delivery.event, None).await?;
+
+            send_discussion_message(
+                state,
+                channel_id,
+                discussion_components(
+                    format!("## <:hammer:1150889684227076227> {title}"),
+                    description.clone(),
+                    body.as_deref(),
+                    &organization.avatar_url.to_string(),
+                    &repository_url,
+                ),
+            )
+            .await?;
+
+            if discussion.action == DiscussionWebhookEventAction::Created
+                && discussion_data.category.is_answerable
+                && delivery.replay_channel_id.is_none()
+                && let Some(forum_channel_id) = state.env.github_discussions_forum_channel_id
+            {
+                // the announcement is already out, failing here would repost it on every retry
+                match create_forum_post(
+                    state,
+                    forum_channel_id,
+                    &format!("#{} {}", discussion_data.number, discussion_data.title),
+                    &discussion_data.category.name,
+                    discussion_components(
+                        format!("## <:hammer:1150889684227076227> {title}"),
+                        description,
+                        body.as_deref(),
+                        &organization.avatar_url.to_string(),
+                        &discussion_data.html_url,
+                    ),
+                )
+                .await
+                {
+                    Ok(thread_id) => {
+                        if let Err(err) = crate::models::create_github_discussion(
+                            state.database.write(),
+                            *repository.id as i64,
+                            discussion_data.number,
+                            forum_channel_id,
+                            thread_id,
+                        )
+                        .await
+                        {
+                            tracing::error!(
+                                "failed to save forum post of discussion #{}: {:?}",
+                                discussion_data.number,
+                                err
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            "failed to forward discussion #{} into forum {forum_channel_id}: {:?}",
+                            discussion_data.number,
+                            err
+                        );
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+        WebhookEventPayload::DiscussionComment(discussion_comment) => {
+            #[derive(Deserialize)]
+            struct CommentData {
+                html_url: String,
+                body: Option<String>,
+            }
+
+            if discussion_comment.action != DiscussionCommentWebhookEventAction::Created {
+                return Ok(());
+            }
+
+            let discussion_data: DiscussionData =
+                serde_json::from_value(discussion_comment.discussion)?;
+
+            // comments are only mirrored into forwarded forum posts
+            let Some(github_discussion) = crate::models::get_github_discussion(
+                state.database.read(),
+                *repository.id as i64,
+                discussion_data.number,
+            )
+            .await?
+            else {
+                return Ok(());
+            };
+
+            let comment: CommentData = serde_json::from_value(discussion_comment.comment)?;
+
+            send_discussion_message(
+                state,
+                delivery
+                    .replay_channel_id
+                    .unwrap_or(github_discussion.thread_id as u64),
+                discussion_components(
+                    "## <:hammer:1150889684227076227> Discussion comment".to_string(),
+                    format!(
+                        "[**{}**]({}) [commented]({}) on [`#{} {}`]({}).",
+                        sender.login,
+                        sender.html_url,
+                        comment.html_url,
+                        discussion_data.number,
+                        discussion_data.title,
+                        discussion_data.html_url
+                    ),
+                    comment.body.as_deref(),
+                    &organization.avatar_url.to_string(),
+                    &repository_url,
+                ),
+            )
+            .await?;
+
+            return Ok(());
+        }
         WebhookEventPayload::Issues(issue) => {
+            if let Some(github_issue) = crate::models::get_github_issue(
+                state.database.read(),
+                *repository.id as i64,
+                issue.issue.number as i64,
+            )
+            .await?
+                && let Some(thread_id) = github_issue.thread_id
+                && delivery.replay_channel_id.is_none()
+            {
+                let Some(activity) = issue_activity(&issue, &sender) else {
+                    return Ok(());
+                };
+                let thread_id = thread_id as u64;
+
+                if issue.action == IssuesWebhookEventAction::Reopened {
+                    crate::models::set_github_issue_closed(
+                        state.database.write(),
+                        github_issue.id,
+                        false,
+                    )
+                    .await?;
+                    set_thread_archived(state, thread_id, false).await?;
+                }
+
+                send_thread_activity(state, thread_id, &activity).await?;
+
+                if issue.action == IssuesWebhookEventAction::Closed {
+                    crate::models::set_github_issue_closed(
+                        state.database.write(),
+                        github_issue.id,
+                        true,
+                    )
+                    .await?;
+                    set_thread_archived(state, thread_id, true).await?;
+                }
+
+                return Ok(());
+            }
+
             // same as pull request but for issues
             match issue.action {
                 IssuesWebhookEventAction::Opened => {
+                    track_github_issue = Some((issue.issue.number, issue.issue.title.clone()));
+
                     container_components.push(CreateContainerComponent::Section(
                         CreateSection::new(
                             vec![
@@ -363,276 +2380,1046 @@ async fn handle_repository_event(
                 _ => {}
             }
         }
+        WebhookEventPayload::Deployment(deployment) => {
+            #[derive(Deserialize)]
+            struct DeploymentData {
+                sha: String,
+                r#ref: String,
+                environment: String,
+                description: Option<String>,
+            }
+
+            let deployment_data: DeploymentData = serde_json::from_value(deployment.deployment)?;
+
+            let mut deployment_string = format!(
+                "[**{}**]({}) started deploying `{}` ([`{}`]({}/commit/{})) to `{}`.",
+                sender.login,
+                sender.html_url,
+                deployment_data.r#ref,
+                deployment_data.sha.chars().take(7).collect::<String>(),
+                repository
+                    .html_url
+                    .as_ref()
+                    .map_or_else(|| "".to_string(), |h| h.to_string()),
+                deployment_data.sha,
+                deployment_data.environment
+            );
+
+            if let Some(description) = deployment_data
+                .description
+                .filter(|description| !description.trim().is_empty())
+            {
+                deployment_string.push_str(&format!(
+                    "\n-# {}",
+                    crate::utils::truncate_text(&description, 200)
+                ));
+            }
+
+            container_components.push(CreateContainerComponent::Section(CreateSection::new(
+                vec![
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                        "## <:clock:1150889651914158111> Deployment to `{}` created",
+                        deployment_data.environment
+                    ))),
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(deployment_string)),
+                ],
+                CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
+                    CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
+                )),
+            )));
+        }
+        WebhookEventPayload::DeploymentStatus(deployment_status) => {
+            #[derive(Deserialize)]
+            struct DeploymentStatusData {
+                state: String,
+                environment: String,
+                description: Option<String>,
+                environment_url: Option<String>,
+                target_url: Option<String>,
+            }
+
+            let status_data: DeploymentStatusData =
+                serde_json::from_value(deployment_status.deployment_status)?;
+
+            let (emoji, title) = match status_data.state.as_str() {
+                "success" => ("<:accept:1156939740654878750>", "succeeded"),
+                "failure" | "error" => ("<:deny:1156939743230173234>", "failed"),
+                "in_progress" => ("<a:loading:1154135013948915793>", "in progress"),
+                "queued" | "pending" => ("<:clock:1150889651914158111>", "queued"),
+                _ => return Ok(()),
+            };
+
+            let mut status_string =
+                format!("The deployment to `{}` {title}.", status_data.environment);
+
+            if let Some(description) = status_data
+                .description
+                .filter(|description| !description.trim().is_empty())
+            {
+                status_string.push_str(&format!(
+                    "\n-# {}",
+                    crate::utils::truncate_text(&description, 200)
+                ));
+            }
+
+            let link = status_data
+                .environment_url
+                .filter(|url| !url.is_empty() && status_data.state == "success")
+                .map(|url| (url, "View Deployment"))
+                .or_else(|| {
+                    status_data
+                        .target_url
+                        .filter(|url| !url.is_empty())
+                        .map(|url| (url, "View Logs"))
+                });
+
+            container_components.push(CreateContainerComponent::Section(CreateSection::new(
+                vec![
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                        "## {emoji} Deployment to `{}` {title}",
+                        status_data.environment
+                    ))),
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(status_string)),
+                ],
+                match link {
+                    Some((url, label)) => {
+                        CreateSectionAccessory::Button(CreateButton::new_link(url).label(label))
+                    }
+                    None => CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
+                        CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
+                    )),
+                },
+            )));
+        }
+        WebhookEventPayload::WorkflowRun(workflow_run) => {
+            #[derive(Deserialize)]
+            struct WorkflowRunData {
+                name: Option<String>,
+                event: String,
+                head_branch: Option<String>,
+                head_sha: String,
+                html_url: String,
+                run_number: i64,
+                display_title: Option<String>,
+            }
+
+            if !state.env.github_workflow_run_messages
+                || workflow_run.action != WorkflowRunWebhookEventAction::Requested
+            {
+                return Ok(());
+            }
+
+            let workflow_run_data: WorkflowRunData =
+                serde_json::from_value(workflow_run.workflow_run)?;
+
+            // push triggered runs are shown in the push message
+            if !matches!(
+                workflow_run_data.event.as_str(),
+                "workflow_dispatch" | "schedule" | "pull_request"
+            ) {
+                return Ok(());
+            }
+
+            // every later run for the same commit joins the first message
+            let existing: Option<i64> = sqlx::query_scalar(
+                "SELECT id FROM github_messages WHERE repository_id = ? AND workflow_sha = ?",
+            )
+            .bind(*repository.id as i64)
+            .bind(&workflow_run_data.head_sha)
+            .fetch_optional(state.database.read())
+            .await?;
+            if existing.is_some() {
+                return Ok(());
+            }
+
+            let branch = workflow_run_data.head_branch.unwrap_or_default();
+            let title = format!(
+                "## <:clock:1150889651914158111> Workflow `{}` run #{}\nTriggered by `{}` on `{}`.",
+                workflow_run_data.name.as_deref().unwrap_or("workflow"),
+                workflow_run_data.run_number,
+                workflow_run_data.event,
+                branch
+            );
+
+            container_components.push(CreateContainerComponent::Section(CreateSection::new(
+                vec![
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(title.clone())),
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                        "[`{}`]({})",
+                        workflow_run_data
+                            .display_title
+                            .as_deref()
+                            .unwrap_or(&workflow_run_data.head_sha),
+                        workflow_run_data.html_url
+                    ))),
+                ],
+                CreateSectionAccessory::Thumbnail(CreateThumbnail::new(
+                    CreateUnfurledMediaItem::new(organization.avatar_url.to_string()),
+                )),
+            )));
+
+            create_github_message = Some((
+                workflow_run_data.head_sha,
+                Vec::new(),
+                branch,
+                Some(title),
+                None,
+            ));
+        }
         WebhookEventPayload::WorkflowJob(workflow_job) => {
+            let workflow_job_data: WorkflowJobData =
+                serde_json::from_value(workflow_job.workflow_job)?;
+
+            let failed_job = (workflow_job_data.conclusion.as_deref() == Some("failure"))
+                .then(|| (repository_name.clone(), workflow_job_data.id));
+
+            let Some(github_message) = apply_workflow_update(
+                state,
+                delivery,
+                *repository.id as i64,
+                &organization.avatar_url.to_string(),
+                WorkflowUpdate {
+                    key: workflow_job_data.id.to_string(),
+                    name: workflow_job_data.name,
+                    source: crate::models::WorkflowSource::Actions,
+                    app: None,
+                    head_sha: workflow_job_data.head_sha,
+                    status: workflow_job_data.status,
+                    conclusion: workflow_job_data.conclusion,
+                    started_at: workflow_job_data.started_at,
+                    completed_at: workflow_job_data.completed_at,
+                    failed_step: None,
+                    failed_job,
+                },
+            )
+            .await?
+            else {
+                return Ok(());
+            };
+
+            edit_github_message = Some((
+                github_message,
+                format!(
+                    "{}/actions/runs/{}",
+                    repository_url, workflow_job_data.run_id
+                ),
+                "View Action",
+            ));
+        }
+        WebhookEventPayload::CheckRun(check_run) => {
+            #[derive(Deserialize)]
+            struct CheckApp {
+                slug: String,
+                name: String,
+            }
+
             #[derive(Deserialize)]
-            struct WorkflowJobData {
+            struct CheckRunData {
                 id: i64,
-                run_id: i64,
                 name: String,
                 head_sha: String,
-                status: octocrab::models::workflows::Status,
-                conclusion: Option<octocrab::models::workflows::Conclusion>,
+                status: String,
+                conclusion: Option<String>,
+                started_at: Option<chrono::DateTime<chrono::Utc>>,
+                completed_at: Option<chrono::DateTime<chrono::Utc>>,
+                html_url: Option<String>,
+                details_url: Option<String>,
+                app: CheckApp,
             }
 
-            let workflow_job_data: WorkflowJobData =
-                serde_json::from_value(workflow_job.workflow_job)?;
+            let check_run_data: CheckRunData = serde_json::from_value(check_run.check_run)?;
+
+            // actions jobs are already tracked through workflow_job events
+            if check_run_data.app.slug == "github-actions" {
+                return Ok(());
+            }
+
+            let Some(github_message) = apply_workflow_update(
+                state,
+                delivery,
+                *repository.id as i64,
+                &organization.avatar_url.to_string(),
+                WorkflowUpdate {
+                    key: format!("check_run:{}", check_run_data.id),
+                    name: check_run_data.name,
+                    source: crate::models::WorkflowSource::CheckRun,
+                    app: Some(check_run_data.app.name),
+                    head_sha: check_run_data.head_sha,
+                    status: check_status(&check_run_data.status),
+                    conclusion: check_run_data.conclusion,
+                    started_at: check_run_data.started_at,
+                    completed_at: check_run_data.completed_at,
+                    failed_step: None,
+                    failed_job: None,
+                },
+            )
+            .await?
+            else {
+                return Ok(());
+            };
+
+            edit_github_message = Some((
+                github_message,
+                check_run_data
+                    .details_url
+                    .or(check_run_data.html_url)
+                    .unwrap_or_else(|| repository_url.clone()),
+                "View Check",
+            ));
+        }
+        WebhookEventPayload::CheckSuite(check_suite) => {
+            #[derive(Deserialize)]
+            struct CheckApp {
+                slug: String,
+                name: String,
+            }
+
+            #[derive(Deserialize)]
+            struct CheckSuiteData {
+                id: i64,
+                head_sha: String,
+                status: Option<String>,
+                conclusion: Option<String>,
+                app: CheckApp,
+            }
+
+            let check_suite_data: CheckSuiteData = serde_json::from_value(check_suite.check_suite)?;
+
+            if check_suite_data.app.slug == "github-actions" {
+                return Ok(());
+            }
 
-            let mut github_message: crate::models::GithubMessage = sqlx::query_as(
-                "SELECT * FROM github_messages WHERE repository_id = ? AND workflow_sha = ?",
+            let has_check_runs = sqlx::query_scalar::<_, String>(
+                "SELECT workflow_status FROM github_messages WHERE repository_id = ? AND workflow_sha = ?",
             )
             .bind(*repository.id as i64)
-            .bind(workflow_job_data.head_sha)
-            .fetch_one(state.database.read())
-            .await?;
+            .bind(&check_suite_data.head_sha)
+            .fetch_optional(state.database.read())
+            .await?
+            .and_then(|workflow_status| {
+                serde_json::from_str::<indexmap::IndexMap<String, crate::models::WorkflowStatus>>(
+                    &workflow_status,
+                )
+                .ok()
+            })
+            .is_some_and(|workflow_status| {
+                workflow_status.values().any(|workflow_status| {
+                    workflow_status.source == crate::models::WorkflowSource::CheckRun
+                        && workflow_status.app.as_deref() == Some(&check_suite_data.app.name)
+                })
+            });
+
+            // individual check runs are more useful than the suite summarising them
+            if has_check_runs {
+                return Ok(());
+            }
 
-            let entry = github_message
-                .workflow_status
-                .entry(workflow_job_data.id)
-                .or_insert_with(|| crate::models::WorkflowStatus {
-                    name: workflow_job_data.name,
-                    status: octocrab::models::workflows::Status::Queued,
-                    started: chrono::Utc::now(),
-                });
+            let Some(github_message) = apply_workflow_update(
+                state,
+                delivery,
+                *repository.id as i64,
+                &organization.avatar_url.to_string(),
+                WorkflowUpdate {
+                    key: format!("check_suite:{}", check_suite_data.id),
+                    name: check_suite_data.app.name.clone(),
+                    source: crate::models::WorkflowSource::CheckSuite,
+                    app: Some(check_suite_data.app.name),
+                    head_sha: check_suite_data.head_sha.clone(),
+                    status: check_status(check_suite_data.status.as_deref().unwrap_or_default()),
+                    conclusion: check_suite_data.conclusion,
+                    started_at: None,
+                    completed_at: None,
+                    failed_step: None,
+                    failed_job: None,
+                },
+            )
+            .await?
+            else {
+                return Ok(());
+            };
 
-            let status = if let Some(conclusion) = workflow_job_data.conclusion {
-                match conclusion {
-                    octocrab::models::workflows::Conclusion::Success
-                    | octocrab::models::workflows::Conclusion::Neutral
-                    | octocrab::models::workflows::Conclusion::Skipped => {
-                        octocrab::models::workflows::Status::Completed
-                    }
-                    _ => octocrab::models::workflows::Status::Failed,
-                }
-            } else {
-                match (&entry.status, &workflow_job_data.status) {
-                    (octocrab::models::workflows::Status::Completed, _) => {
-                        octocrab::models::workflows::Status::Completed
-                    }
-                    (octocrab::models::workflows::Status::Failed, _) => {
-                        octocrab::models::workflows::Status::Failed
-                    }
-                    (
-                        octocrab::models::workflows::Status::InProgress,
-                        octocrab::models::workflows::Status::Queued
-                        | octocrab::models::workflows::Status::Pending
-                        | octocrab::models::workflows::Status::Waiting,
-                    ) => octocrab::models::workflows::Status::InProgress,
-                    _ => workflow_job_data.status,
-                }
+            edit_github_message = Some((
+                github_message,
+                format!(
+                    "{}/commit/{}/checks",
+                    repository_url, check_suite_data.head_sha
+                ),
+                "View Checks",
+            ));
+        }
+        WebhookEventPayload::Status(status) => {
+            let (workflow_status, conclusion) = match status.state {
+                CommitState::Pending => (octocrab::models::workflows::Status::Pending, None),
+                CommitState::Success => (
+                    octocrab::models::workflows::Status::Completed,
+                    Some("success"),
+                ),
+                CommitState::Error => (octocrab::models::workflows::Status::Failed, Some("error")),
+                _ => (octocrab::models::workflows::Status::Failed, Some("failure")),
+            };
+
+            let Some(github_message) = apply_workflow_update(
+                state,
+                delivery,
+                *repository.id as i64,
+                &organization.avatar_url.to_string(),
+                WorkflowUpdate {
+                    key: format!("status:{}", status.context),
+                    name: status.context.clone(),
+                    source: crate::models::WorkflowSource::Status,
+                    app: None,
+                    head_sha: status.sha.clone(),
+                    status: workflow_status,
+                    conclusion: conclusion.map(|conclusion| conclusion.to_string()),
+                    started_at: None,
+                    completed_at: None,
+                    failed_step: None,
+                    failed_job: None,
+                },
+            )
+            .await?
+            else {
+                return Ok(());
             };
-            entry.status = status;
 
-            sqlx::query("UPDATE github_messages SET workflow_status = ? WHERE id = ?")
-                .bind(serde_json::to_string(&github_message.workflow_status)?)
-                .bind(github_message.id)
-                .execute(state.database.write())
-                .await?;
+            edit_github_message = Some((
+                github_message,
+                status.target_url.as_ref().map_or_else(
+                    || format!("{}/commit/{}", repository_url, status.sha),
+                    |target_url| target_url.to_string(),
+                ),
+                "View Status",
+            ));
+        }
+        _ => {
+            return Ok(());
+        }
+    };
+
+    let channel_id = match &edit_github_message {
+        Some((github_message, _, _)) if delivery.replay_channel_id.is_none() => {
+            github_message.channel_id.map_or_else(
+                || fallback_channel_id(state, delivery.source.as_deref()),
+                |channel_id| channel_id as u64,
+            )
+        }
+        _ => {
+            route_channel_id(
+                state,
+                delivery,
+                &repository_name,
+                &delivery.event,
+                route_branch.as_deref(),
+            )
+            .await?
+        }
+    };
+
+    let Some(channel) = state
+        .bot
+        .read()
+        .await
+        .get_channel(channel_id.into())
+        .await?
+        .guild()
+    else {
+        return Err(anyhow::anyhow!(
+            "github webhook channel ID {} is not a guild channel",
+            channel_id
+        ));
+    };
+
+    let mut tracked_github_message = None;
+    if let Some((edit_github_message, link, link_label)) = edit_github_message {
+        let mut commit_string = String::new();
+
+        for commit in edit_github_message.commits.iter().take(8) {
+            commit_string.push_str(&format!(
+                "[`{}`]({}) ({}): {}\n",
+                commit.id.chars().take(7).collect::<String>(),
+                commit.url,
+                commit.author.user.name,
+                commit.message.lines().next().unwrap_or_default()
+            ));
+        }
+
+        if edit_github_message.commits.len() > 8 {
+            commit_string.push_str(&format!(
+                "-# ... {} more Commits\n",
+                edit_github_message.commits.len() - 8
+            ));
+        }
+
+        container_components.push(CreateContainerComponent::Section(CreateSection::new(
+            match &edit_github_message.title {
+                Some(title) => vec![CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                    title.clone(),
+                ))],
+                None => vec![
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                        "## <:package:1150890021516234832> {} Commit{} pushed to `{}`{}",
+                        edit_github_message.commits.len(),
+                        if edit_github_message.commits.len() == 1 {
+                            ""
+                        } else {
+                            "s"
+                        },
+                        edit_github_message.branch,
+                        if edit_github_message.forced {
+                            " (force-pushed)"
+                        } else {
+                            ""
+                        }
+                    ))),
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(commit_string)),
+                ],
+            },
+            CreateSectionAccessory::Thumbnail(CreateThumbnail::new(CreateUnfurledMediaItem::new(
+                organization.avatar_url.to_string(),
+            ))),
+        )));
+        if edit_github_message.workflow_status.is_empty() {
+            container_components.push(CreateContainerComponent::ActionRow(
+                CreateActionRow::Buttons(
+                    vec![CreateButton::new_link(link).label(link_label)].into(),
+                ),
+            ));
+        } else {
+            container_components.push(CreateContainerComponent::Separator(CreateSeparator::new()));
+
+            container_components.push(CreateContainerComponent::Section(CreateSection::new(
+                vec![
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Workflow Status",
+                    )),
+                    CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                        workflow_status_string(&edit_github_message.workflow_status),
+                    )),
+                ],
+                CreateSectionAccessory::Button(CreateButton::new_link(link).label(link_label)),
+            )));
+        }
+
+        tracked_github_message = Some((edit_github_message.id, edit_github_message.message_id));
+    }
+
+    if container_components.is_empty() {
+        return Ok(());
+    }
+
+    container_components.push(CreateContainerComponent::TextDisplay(
+        CreateTextDisplay::new(format!(
+            "-# {}",
+            repository
+                .html_url
+                .map_or_else(|| repository.name, |h| h.to_string())
+        )),
+    ));
+    let component = CreateComponent::Container(CreateContainer::new(container_components));
+
+    if let Some((_, message_id)) = tracked_github_message
+        && delivery.replay_channel_id.is_none()
+        && edit_tracked_message(
+            state,
+            delivery,
+            channel.id.get(),
+            message_id as u64,
+            &component,
+        )
+        .await?
+    {
+        return Ok(());
+    }
+
+    let message = channel
+        .send_message(
+            &*state.bot.read().await,
+            CreateMessage::new()
+                .components(&[component])
+                .flags(MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+
+    // a dry run stops at the message, nothing it sent is tracked
+    if delivery.replay_channel_id.is_some() {
+        return Ok(());
+    }
+
+    if let Some((id, _)) = tracked_github_message {
+        crate::models::set_github_message_id(
+            state.database.write(),
+            id,
+            channel.id.get(),
+            message.id.get(),
+        )
+        .await?;
+    }
+
+    if let Some((id, milestone)) = reached_star_milestone {
+        crate::models::set_github_star_milestone(state.database.write(), id, milestone).await?;
+    }
+
+    if let Some((workflow_sha, commits, branch, title, before_sha)) = create_github_message {
+        // a replay resending a deleted message keeps the row and its workflow status
+        let replayed_github_message: Option<i64> = if delivery.replayed {
+            sqlx::query_scalar(
+                "SELECT id FROM github_messages WHERE repository_id = ? AND workflow_sha = ?",
+            )
+            .bind(*repository.id as i64)
+            .bind(&workflow_sha)
+            .fetch_optional(state.database.read())
+            .await?
+        } else {
+            None
+        };
+
+        match replayed_github_message {
+            Some(id) => {
+                crate::models::set_github_message_id(
+                    state.database.write(),
+                    id,
+                    channel.id.get(),
+                    message.id.get(),
+                )
+                .await?;
+            }
+            // the same commit pushed to another branch takes over the message its workflows update
+            None => {
+                sqlx::query("INSERT INTO github_messages (repository_id, channel_id, message_id, commits, branch, title, before_sha, workflow_sha, workflow_status) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                     ON CONFLICT (repository_id, workflow_sha) DO UPDATE SET channel_id = excluded.channel_id, message_id = excluded.message_id, commits = excluded.commits, branch = excluded.branch, title = excluded.title, forced = false, before_sha = excluded.before_sha, updated = excluded.created")
+                    .bind(*repository.id as i64)
+                    .bind(channel.id.get() as i64)
+                    .bind(message.id.get() as i64)
+                    .bind(serde_json::to_string(&commits)?)
+                    .bind(branch)
+                    .bind(title)
+                    .bind(before_sha)
+                    .bind(&workflow_sha)
+                    .bind("{}")
+                    .execute(state.database.write())
+                    .await?;
+            }
+        }
+
+        // jobs that raced ahead of this message can be attached to it now
+        if crate::models::requeue_deferred_github_webhook_events(
+            state.database.write(),
+            *repository.id as i64,
+            &workflow_sha,
+        )
+        .await?
+            > 0
+        {
+            GITHUB_EVENTS_NOTIFY.notify_one();
+        }
+    }
+
+    if let Some((number, title)) = track_github_issue {
+        let replayed_github_issue = if delivery.replayed {
+            crate::models::get_github_issue(
+                state.database.read(),
+                *repository.id as i64,
+                number as i64,
+            )
+            .await?
+        } else {
+            None
+        };
+
+        // a replay resending a deleted message keeps the issue's thread
+        let github_issue = match replayed_github_issue {
+            Some(github_issue) => {
+                crate::models::set_github_issue_message(
+                    state.database.write(),
+                    github_issue.id,
+                    channel.id.get(),
+                    message.id.get(),
+                )
+                .await?;
+
+                github_issue
+            }
+            None => {
+                crate::models::create_github_issue(
+                    state.database.write(),
+                    *repository.id as i64,
+                    number as i64,
+                    channel.id.get(),
+                    message.id.get(),
+                )
+                .await?
+            }
+        };
+
+        if state.env.github_threads
+            && github_issue.thread_id.is_none()
+            && let Some(thread_id) = create_discussion_thread(
+                state,
+                channel.id.get(),
+                message.id.get(),
+                &format!("#{number} {title}"),
+            )
+            .await
+        {
+            crate::models::set_github_issue_thread(
+                state.database.write(),
+                github_issue.id,
+                thread_id,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+struct SecurityAlertUpdate {
+    kind: &'static str,
+    key: String,
+    state: String,
+    severity: Option<String>,
+    summary: String,
+    package: Option<String>,
+    vulnerable_range: Option<String>,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct SecurityPackage {
+    ecosystem: String,
+    name: String,
+}
+
+impl SecurityPackage {
+    #[inline]
+    fn describe(&self) -> String {
+        format!("{} ({})", self.name, self.ecosystem)
+    }
+}
+
+fn security_alert_update(
+    specific: WebhookEventPayload,
+) -> Result<Option<SecurityAlertUpdate>, anyhow::Error> {
+    Ok(Some(match specific {
+        WebhookEventPayload::DependabotAlert(dependabot_alert) => {
+            #[derive(Deserialize)]
+            struct Dependency {
+                package: SecurityPackage,
+            }
+
+            #[derive(Deserialize)]
+            struct Advisory {
+                summary: String,
+                severity: String,
+            }
+
+            #[derive(Deserialize)]
+            struct Vulnerability {
+                vulnerable_version_range: String,
+            }
+
+            #[derive(Deserialize)]
+            struct DependabotAlertData {
+                number: i64,
+                state: String,
+                html_url: String,
+                dependency: Dependency,
+                security_advisory: Advisory,
+                security_vulnerability: Vulnerability,
+            }
+
+            let alert: DependabotAlertData = serde_json::from_value(dependabot_alert.alert)?;
+
+            SecurityAlertUpdate {
+                kind: "dependabot_alert",
+                key: alert.number.to_string(),
+                state: alert.state,
+                severity: Some(alert.security_advisory.severity),
+                summary: alert.security_advisory.summary,
+                package: Some(alert.dependency.package.describe()),
+                vulnerable_range: Some(alert.security_vulnerability.vulnerable_version_range),
+                url: alert.html_url,
+            }
+        }
+        WebhookEventPayload::RepositoryVulnerabilityAlert(vulnerability_alert) => {
+            #[derive(Deserialize)]
+            struct VulnerabilityAlertData {
+                number: i64,
+                state: String,
+                affected_package_name: String,
+                affected_range: String,
+                severity: String,
+                ghsa_id: String,
+            }
 
-            edit_github_message = Some((github_message, workflow_job_data.run_id));
+            let alert: VulnerabilityAlertData = serde_json::from_value(vulnerability_alert.alert)?;
+
+            SecurityAlertUpdate {
+                kind: "repository_vulnerability_alert",
+                key: alert.number.to_string(),
+                state: alert.state,
+                severity: Some(alert.severity),
+                summary: alert.ghsa_id.clone(),
+                package: Some(alert.affected_package_name),
+                vulnerable_range: Some(alert.affected_range),
+                url: format!("https://github.com/advisories/{}", alert.ghsa_id),
+            }
         }
-        _ => {
-            return Ok(());
+        WebhookEventPayload::SecretScanningAlert(secret_scanning_alert) => {
+            #[derive(Deserialize)]
+            struct SecretScanningAlertData {
+                number: i64,
+                state: String,
+                html_url: String,
+                secret_type_display_name: Option<String>,
+                secret_type: String,
+            }
+
+            let alert: SecretScanningAlertData =
+                serde_json::from_value(secret_scanning_alert.alert)?;
+
+            SecurityAlertUpdate {
+                kind: "secret_scanning_alert",
+                key: alert.number.to_string(),
+                state: alert.state,
+                severity: None,
+                summary: format!(
+                    "Exposed {}",
+                    alert.secret_type_display_name.unwrap_or(alert.secret_type)
+                ),
+                package: None,
+                vulnerable_range: None,
+                url: alert.html_url,
+            }
         }
-    };
+        WebhookEventPayload::CodeScanningAlert(code_scanning_alert) => {
+            #[derive(Deserialize)]
+            struct Rule {
+                description: String,
+                severity: Option<String>,
+                security_severity_level: Option<String>,
+            }
 
-    let Some(channel) = state
-        .bot
-        .read()
-        .await
-        .get_channel(state.env.github_channel_id.into())
-        .await?
-        .guild()
-    else {
-        return Err(anyhow::anyhow!(
-            "github webhook channel ID {} is not a guild channel",
-            state.env.github_channel_id
-        ));
-    };
+            #[derive(Deserialize)]
+            struct Tool {
+                name: String,
+            }
 
-    if let Some((edit_github_message, run_id)) = edit_github_message {
-        let mut commit_string = String::new();
+            #[derive(Deserialize)]
+            struct CodeScanningAlertData {
+                number: i64,
+                state: String,
+                html_url: String,
+                rule: Rule,
+                tool: Tool,
+            }
 
-        for commit in edit_github_message.commits.iter().take(8) {
-            commit_string.push_str(&format!(
-                "[`{}`]({}) ({}): {}\n",
-                commit.id.chars().take(7).collect::<String>(),
-                commit.url,
-                commit.author.user.name,
-                commit.message.lines().next().unwrap_or_default()
-            ));
+            let alert: CodeScanningAlertData = serde_json::from_value(code_scanning_alert.alert)?;
+
+            SecurityAlertUpdate {
+                kind: "code_scanning_alert",
+                key: alert.number.to_string(),
+                state: alert.state,
+                severity: alert.rule.security_severity_level.or(alert.rule.severity),
+                summary: alert.rule.description,
+                package: Some(alert.tool.name),
+                vulnerable_range: None,
+                url: alert.html_url,
+            }
         }
+        WebhookEventPayload::SecurityAdvisory(security_advisory) => {
+            #[derive(Deserialize)]
+            struct Vulnerability {
+                package: SecurityPackage,
+                vulnerable_version_range: String,
+            }
 
-        if edit_github_message.commits.len() > 8 {
-            commit_string.push_str(&format!(
-                "-# ... {} more Commits\n",
-                edit_github_message.commits.len() - 8
-            ));
-        }
+            #[derive(Deserialize)]
+            struct SecurityAdvisoryData {
+                ghsa_id: String,
+                summary: String,
+                severity: String,
+                withdrawn_at: Option<String>,
+                #[serde(default)]
+                vulnerabilities: Vec<Vulnerability>,
+            }
 
-        container_components.push(CreateContainerComponent::Section(CreateSection::new(
-            vec![
-                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
-                    "## <:package:1150890021516234832> {} Commit{} pushed to `{}`",
-                    edit_github_message.commits.len(),
-                    if edit_github_message.commits.len() == 1 {
-                        ""
-                    } else {
-                        "s"
-                    },
-                    edit_github_message.branch
-                ))),
-                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(commit_string)),
-            ],
-            CreateSectionAccessory::Thumbnail(CreateThumbnail::new(CreateUnfurledMediaItem::new(
-                organization.avatar_url.to_string(),
-            ))),
-        )));
-        container_components.push(CreateContainerComponent::Separator(CreateSeparator::new()));
+            let advisory: SecurityAdvisoryData =
+                serde_json::from_value(security_advisory.security_advisory)?;
 
-        let mut workflow_status_string = String::new();
-
-        for workflow_status in edit_github_message.workflow_status.values() {
-            workflow_status_string.push_str(&format!(
-                "{} **{}** <t:{}:R>\n",
-                match workflow_status.status {
-                    octocrab::models::workflows::Status::Completed =>
-                        "<:accept:1156939740654878750>",
-                    octocrab::models::workflows::Status::InProgress =>
-                        "<a:loading:1154135013948915793>",
-                    octocrab::models::workflows::Status::Failed => "<:deny:1156939743230173234>",
-                    _ => "<:clock:1150889651914158111>",
+            SecurityAlertUpdate {
+                kind: "security_advisory",
+                key: advisory.ghsa_id.clone(),
+                state: if advisory.withdrawn_at.is_some() {
+                    "withdrawn".to_string()
+                } else {
+                    match security_advisory.action {
+                        SecurityAdvisoryWebhookEventAction::Updated => "updated".to_string(),
+                        _ => "published".to_string(),
+                    }
                 },
-                workflow_status.name,
-                workflow_status.started.timestamp()
-            ));
+                severity: Some(advisory.severity),
+                summary: advisory.summary,
+                package: (!advisory.vulnerabilities.is_empty()).then(|| {
+                    advisory
+                        .vulnerabilities
+                        .iter()
+                        .map(|vulnerability| vulnerability.package.describe())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }),
+                vulnerable_range: (!advisory.vulnerabilities.is_empty()).then(|| {
+                    advisory
+                        .vulnerabilities
+                        .iter()
+                        .map(|vulnerability| vulnerability.vulnerable_version_range.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }),
+                url: format!("https://github.com/advisories/{}", advisory.ghsa_id),
+            }
         }
+        _ => return Ok(None),
+    }))
+}
 
-        container_components.push(CreateContainerComponent::Section(CreateSection::new(
-            vec![
-                CreateSectionComponent::TextDisplay(CreateTextDisplay::new("### Workflow Status")),
-                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(workflow_status_string)),
-            ],
-            CreateSectionAccessory::Button(
-                CreateButton::new_link(format!(
-                    "{}/actions/runs/{}",
-                    repository
-                        .html_url
-                        .as_ref()
-                        .map_or_else(|| "".to_string(), |h| h.to_string()),
-                    run_id
-                ))
-                .label("View Action"),
-            ),
-        )));
-
-        let mut message = state
-            .bot
-            .read()
-            .await
-            .get_message(
-                channel.id.into(),
-                (edit_github_message.message_id as u64).into(),
-            )
-            .await?;
+/// Security events never fall back to the public channels, they are dropped
+/// unless `GITHUB_SECURITY_CHANNEL_ID` is configured
+async fn handle_security_event(
+    state: &State,
+    event: octocrab::models::webhook_events::WebhookEvent,
+) -> Result<(), anyhow::Error> {
+    let Some(channel_id) = state.env.github_security_channel_id else {
+        return Ok(());
+    };
 
-        container_components.push(CreateContainerComponent::TextDisplay(
-            CreateTextDisplay::new(format!(
-                "-# {}",
-                repository
-                    .html_url
-                    .map_or_else(|| repository.name, |h| h.to_string())
-            )),
-        ));
-        let component = CreateComponent::Container(CreateContainer::new(container_components));
+    let repository_name = event
+        .repository
+        .as_ref()
+        .map_or_else(String::new, |repository| {
+            repository
+                .full_name
+                .clone()
+                .unwrap_or_else(|| repository.name.clone())
+        });
+
+    let Some(update) = security_alert_update(event.specific)? else {
+        return Ok(());
+    };
 
-        message
-            .edit(
-                &*state.bot.read().await,
-                serenity::all::EditMessage::new()
-                    .components(&[component])
-                    .flags(MessageFlags::IS_COMPONENTS_V2),
+    let mut alert = match crate::models::get_github_security_alert(
+        state.database.read(),
+        update.kind,
+        &repository_name,
+        &update.key,
+    )
+    .await?
+    {
+        Some(alert) => alert,
+        None => {
+            crate::models::create_github_security_alert(
+                state.database.write(),
+                update.kind,
+                &repository_name,
+                &update.key,
+                channel_id,
             )
-            .await?;
+            .await?
+        }
+    };
 
-        return Ok(());
-    } else if !container_components.is_empty() {
-        container_components.push(CreateContainerComponent::TextDisplay(
-            CreateTextDisplay::new(format!(
-                "-# {}",
-                repository
-                    .html_url
-                    .map_or_else(|| repository.name, |h| h.to_string())
-            )),
-        ));
-        let component = CreateComponent::Container(CreateContainer::new(container_components));
+    // a reopened alert needs someone to take it again
+    let was_open = alert.state.is_empty() || alert.is_open();
 
-        let message = channel
-            .send_message(
-                &*state.bot.read().await,
-                CreateMessage::new()
-                    .components(&[component])
-                    .flags(MessageFlags::IS_COMPONENTS_V2),
-            )
-            .await?;
+    alert.state = update.state;
+    alert.severity = update.severity;
+    alert.summary = update.summary;
+    alert.package = update.package;
+    alert.vulnerable_range = update.vulnerable_range;
+    alert.url = update.url;
 
-        if let Some((head_commit, commits, branch)) = create_github_message {
-            sqlx::query("INSERT INTO github_messages (repository_id, message_id, commits, branch, workflow_sha, workflow_status) VALUES (?, ?, ?, ?, ?, ?)")
-                .bind(*repository.id as i64)
-                .bind(message.id.get() as i64)
-                .bind(serde_json::to_string(&commits)?)
-                .bind(branch)
-                .bind(head_commit.id)
-                .bind("{}")
-                .execute(state.database.write())
-                .await?;
-        }
+    if !was_open && alert.is_open() {
+        alert.acknowledged_by = None;
+        alert.acknowledged = None;
     }
 
+    crate::models::update_github_security_alert(state.database.write(), &alert).await?;
+    alert
+        .send_or_update(&*state.bot.read().await, &state.database)
+        .await?;
+
     Ok(())
 }
 
 async fn handle_organization_event(
     state: &State,
+    delivery: &crate::models::GithubWebhookEvent,
     event: octocrab::models::webhook_events::WebhookEvent,
 ) -> Result<(), anyhow::Error> {
     let mut container_components = Vec::new();
-    let mut channel_id = state.env.github_channel_id;
+    let mut channel_id = None;
+
+    let organization_login = event
+        .organization
+        .as_ref()
+        .map_or_else(String::new, |organization| organization.login.clone());
+    let repository_name = event.repository.as_ref().map(|repository| {
+        repository
+            .full_name
+            .clone()
+            .unwrap_or_else(|| repository.name.clone())
+    });
 
-    if let WebhookEventPayload::Sponsorship(sponsorship) = event.specific
-        && sponsorship.action == SponsorshipWebhookEventAction::Created
-    {
-        if state.env.github_token.is_some() {
-            return Ok(());
-        }
+    match event.specific {
+        WebhookEventPayload::Sponsorship(sponsorship) if crate::github::configured(&state.env) => {
+            // the sponsors task announces and updates these itself when it can query the api,
+            // wake it up so it does not wait for the next poll
+            if matches!(
+                sponsorship.action,
+                SponsorshipWebhookEventAction::Created
+                    | SponsorshipWebhookEventAction::Cancelled
+                    | SponsorshipWebhookEventAction::TierChanged
+                    | SponsorshipWebhookEventAction::PendingCancellation
+                    | SponsorshipWebhookEventAction::PendingTierChange
+            ) {
+                crate::sponsors::trigger_sponsor_update();
+            }
 
-        #[derive(Deserialize)]
-        struct SponsorshipMaintainer {
-            avatar_url: String,
-            login: String,
+            return Ok(());
         }
+        WebhookEventPayload::Sponsorship(sponsorship)
+            if sponsorship.action == SponsorshipWebhookEventAction::Created =>
+        {
+            #[derive(Deserialize)]
+            struct SponsorshipMaintainer {
+                avatar_url: String,
+                login: String,
+            }
 
-        #[derive(Deserialize)]
-        struct SponsorshipSponsor {
-            avatar_url: String,
-            login: String,
-        }
+            #[derive(Deserialize)]
+            struct SponsorshipSponsor {
+                avatar_url: String,
+                login: String,
+            }
 
-        #[derive(Deserialize)]
-        struct SponsorshipTier {
-            monthly_price_in_dollars: i64,
-        }
+            #[derive(Deserialize)]
+            struct SponsorshipTier {
+                monthly_price_in_dollars: i64,
+            }
 
-        #[derive(Deserialize)]
-        struct SponsorshipData {
-            maintainer: SponsorshipMaintainer,
-            sponsor: Option<SponsorshipSponsor>,
-            privacy_level: String,
-            tier: SponsorshipTier,
-        }
+            #[derive(Deserialize)]
+            struct SponsorshipData {
+                maintainer: SponsorshipMaintainer,
+                sponsor: Option<SponsorshipSponsor>,
+                privacy_level: String,
+                tier: SponsorshipTier,
+            }
 
-        let sponsorship_data: SponsorshipData = serde_json::from_value(sponsorship.sponsorship)?;
+            let sponsorship_data: SponsorshipData =
+                serde_json::from_value(sponsorship.sponsorship)?;
 
-        container_components.push(CreateContainerComponent::Section(CreateSection::new(
+            container_components.push(CreateContainerComponent::Section(CreateSection::new(
             vec![
                 CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
                     "## <:cash:1150889514236137605> Sponsorship received",
@@ -661,18 +3448,36 @@ async fn handle_organization_event(
                 ),
             ))),
         )));
-        container_components.push(CreateContainerComponent::TextDisplay(
-            CreateTextDisplay::new(format!(
-                "-# https://github.com/sponsors/{}",
-                sponsorship_data.maintainer.login
-            )),
-        ));
+            container_components.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(format!(
+                    "-# https://github.com/sponsors/{}",
+                    sponsorship_data.maintainer.login
+                )),
+            ));
 
-        if let Some(sponsors_channel_id) = state.env.github_sponsors_channel_id {
-            channel_id = sponsors_channel_id;
+            channel_id = state.env.github_sponsors_channel_id;
         }
+        _ => {}
+    }
+
+    if container_components.is_empty() {
+        return Ok(());
     }
 
+    let channel_id = match delivery.replay_channel_id.or(channel_id) {
+        Some(channel_id) => channel_id,
+        None => {
+            route_channel_id(
+                state,
+                delivery,
+                repository_name.as_deref().unwrap_or(&organization_login),
+                &delivery.event,
+                None,
+            )
+            .await?
+        }
+    };
+
     let Some(channel) = state
         .bot
         .read()
@@ -687,18 +3492,17 @@ async fn handle_organization_event(
         ));
     };
 
-    if !container_components.is_empty() {
-        let component = CreateComponent::Container(CreateContainer::new(container_components));
+    let component = CreateComponent::Container(CreateContainer::new(container_components));
 
-        channel
-            .send_message(
-                &*state.bot.read().await,
-                CreateMessage::new()
-                    .components(&[component])
-                    .flags(MessageFlags::IS_COMPONENTS_V2),
-            )
-            .await?;
-    }
+    channel
+        .send_message(
+            &*state.bot.read().await,
+            CreateMessage::new()
+                .components(&[component])
+                .flags(MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
 
     Ok(())
 }
@@ -706,7 +3510,7 @@ async fn handle_organization_event(
 mod post {
     use crate::{
         response::{ApiResponse, ApiResponseResult},
-        routes::{ApiError, GetState, github::GITHUB_TX},
+        routes::{ApiError, GetState, State, github::GITHUB_EVENTS_NOTIFY},
     };
     use axum::http::StatusCode;
     use hmac::{KeyInit, Mac};
@@ -714,17 +3518,16 @@ mod post {
     use utoipa::ToSchema;
 
     #[derive(ToSchema, Serialize)]
-    struct Response {}
+    pub(super) struct Response {
+        duplicate: bool,
+    }
 
-    #[utoipa::path(post, path = "/", responses(
-        (status = OK, body = inline(Response)),
-        (status = BAD_REQUEST, body = ApiError),
-        (status = UNAUTHORIZED, body = ApiError),
-    ), request_body = String)]
-    pub async fn route(
-        state: GetState,
-        headers: axum::http::HeaderMap,
-        data: axum::body::Bytes,
+    /// Verifies a delivery against the secrets of the webhook it came in through and queues it
+    pub(super) async fn receive(
+        state: &State,
+        source: Option<&crate::env::GithubSource>,
+        headers: &axum::http::HeaderMap,
+        data: &[u8],
     ) -> ApiResponseResult {
         let Some(signature_header) = headers.get("X-Hub-Signature-256") else {
             return ApiResponse::error("missing X-Hub-Signature-256 header")
@@ -749,13 +3552,26 @@ mod post {
             }
         };
 
-        let mut mac: hmac::Hmac<sha2::Sha256> =
-            hmac::Hmac::new_from_slice(state.env.github_verify_token.as_bytes()).map_err(|e| {
-                ApiResponse::error(&e.to_string()).with_status(StatusCode::INTERNAL_SERVER_ERROR)
-            })?;
-        mac.update(&data);
+        let verify_tokens = source.map_or(&state.env.github_verify_tokens, |source| {
+            &source.verify_tokens
+        });
+
+        let mut verified = false;
+        for verify_token in verify_tokens {
+            let mut mac: hmac::Hmac<sha2::Sha256> =
+                hmac::Hmac::new_from_slice(verify_token.as_bytes()).map_err(|e| {
+                    ApiResponse::error(&e.to_string())
+                        .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                })?;
+            mac.update(data);
+
+            if mac.verify_slice(&signature_bytes).is_ok() {
+                verified = true;
+                break;
+            }
+        }
 
-        if mac.verify_slice(&signature_bytes).is_err() {
+        if !verified {
             return ApiResponse::error("invalid webhook signature")
                 .with_status(StatusCode::UNAUTHORIZED)
                 .ok();
@@ -769,28 +3585,320 @@ mod post {
 
         let event_name = event.to_str()?;
         let event = match octocrab::models::webhook_events::WebhookEvent::try_from_header_and_body(
-            event_name, &data,
+            event_name, data,
         ) {
             Ok(event) => event,
             Err(err) => {
                 tracing::warn!("failed to parse github {event_name} event: {:?}", err);
 
-                return ApiResponse::json(Response {}).ok();
+                return ApiResponse::json(Response { duplicate: false }).ok();
             }
         };
 
         if event.kind == octocrab::models::webhook_events::WebhookEventType::Ping {
-            return ApiResponse::json(Response {}).ok();
+            return ApiResponse::json(Response { duplicate: false }).ok();
+        }
+
+        let delivery_id = headers
+            .get("X-GitHub-Delivery")
+            .and_then(|delivery_id| delivery_id.to_str().ok());
+
+        if crate::models::insert_github_webhook_event(
+            state.database.write(),
+            delivery_id,
+            source.map(|source| source.name.as_str()),
+            event_name,
+            std::str::from_utf8(data)?,
+        )
+        .await?
+        .is_none()
+        {
+            tracing::debug!(
+                "ignoring duplicate github {event_name} delivery {}",
+                delivery_id.unwrap_or_default()
+            );
+
+            return ApiResponse::json(Response { duplicate: true })
+                .with_status(StatusCode::ALREADY_REPORTED)
+                .ok();
+        }
+
+        GITHUB_EVENTS_NOTIFY.notify_one();
+
+        ApiResponse::json(Response { duplicate: false }).ok()
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = ALREADY_REPORTED, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = UNAUTHORIZED, body = ApiError),
+    ), request_body = String)]
+    pub async fn route(
+        state: GetState,
+        headers: axum::http::HeaderMap,
+        data: axum::body::Bytes,
+    ) -> ApiResponseResult {
+        receive(&state, None, &headers, &data).await
+    }
+}
+
+mod post_source {
+    use super::post::{Response, receive};
+    use crate::{
+        response::{ApiResponse, ApiResponseResult},
+        routes::{ApiError, GetState},
+    };
+    use axum::{extract::Path, http::StatusCode};
+
+    #[utoipa::path(post, path = "/{source}", responses(
+        (status = OK, body = inline(Response)),
+        (status = ALREADY_REPORTED, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = UNAUTHORIZED, body = ApiError),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        ("source" = String, Path, description = "The webhook source, as configured in GITHUB_SOURCES"),
+    ), request_body = String)]
+    pub async fn route(
+        state: GetState,
+        Path(source): Path<String>,
+        headers: axum::http::HeaderMap,
+        data: axum::body::Bytes,
+    ) -> ApiResponseResult {
+        let Some(github_source) = state
+            .env
+            .github_sources
+            .iter()
+            .find(|github_source| github_source.name == source)
+        else {
+            return ApiResponse::error("unknown webhook source")
+                .with_status(StatusCode::NOT_FOUND)
+                .ok();
+        };
+
+        receive(&state, Some(github_source), &headers, &data).await
+    }
+}
+
+mod post_replay {
+    use crate::{
+        response::{ApiResponse, ApiResponseResult},
+        routes::{ApiError, GetState},
+    };
+    use axum::{extract::Path, http::StatusCode};
+    use serde::{Deserialize, Serialize};
+    use sha2::Digest;
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Deserialize, Default)]
+    pub struct Payload {
+        /// Send new messages here instead of their routed channel, for dry runs
+        channel_id: Option<u64>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        event: String,
+    }
+
+    #[utoipa::path(post, path = "/replay/{delivery}", responses(
+        (status = OK, body = inline(Response)),
+        (status = UNAUTHORIZED, body = ApiError),
+        (status = NOT_FOUND, body = ApiError),
+        (status = INTERNAL_SERVER_ERROR, body = ApiError),
+    ), params(
+        ("delivery" = String, Path, description = "The X-GitHub-Delivery ID of a stored delivery"),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        Path(delivery): Path<String>,
+        headers: axum::http::HeaderMap,
+        data: Option<axum::Json<Payload>>,
+    ) -> ApiResponseResult {
+        let Some(replay_token) = &state.env.github_replay_token else {
+            return ApiResponse::error("replays are not enabled")
+                .with_status(StatusCode::NOT_FOUND)
+                .ok();
+        };
+
+        let authorization = headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|authorization| authorization.to_str().ok())
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .unwrap_or_default();
+
+        // hashed first so the comparison does not leak how much of the token matched
+        if sha2::Sha256::digest(authorization) != sha2::Sha256::digest(replay_token) {
+            return ApiResponse::error("invalid replay token")
+                .with_status(StatusCode::UNAUTHORIZED)
+                .ok();
         }
 
-        GITHUB_TX.send((state.0, event, 1))?;
+        let data = data.map(|data| data.0).unwrap_or_default();
 
-        ApiResponse::json(Response {}).ok()
+        match super::replay_github_event(&state, &delivery, data.channel_id).await {
+            Ok(Some(replayed)) => ApiResponse::json(Response {
+                event: replayed.event,
+            })
+            .ok(),
+            Ok(None) => ApiResponse::error("delivery not found")
+                .with_status(StatusCode::NOT_FOUND)
+                .ok(),
+            Err(err) => {
+                tracing::error!("failed to replay github delivery {delivery}: {:?}", err);
+
+                ApiResponse::error(&format!("{err:#}"))
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok()
+            }
+        }
     }
 }
 
 pub fn router(state: &State) -> OpenApiRouter<State> {
     OpenApiRouter::new()
         .routes(routes!(post::route))
+        .routes(routes!(post_source::route))
+        .routes(routes!(post_replay::route))
         .with_state(state.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use octocrab::models::workflows::Status;
+
+    fn update(key: &str, status: Status, conclusion: Option<&str>) -> super::WorkflowUpdate {
+        super::WorkflowUpdate {
+            key: key.to_string(),
+            name: "build".to_string(),
+            source: crate::models::WorkflowSource::CheckRun,
+            app: Some("ci".to_string()),
+            head_sha: "abc".to_string(),
+            status,
+            conclusion: conclusion.map(str::to_string),
+            started_at: None,
+            completed_at: None,
+            failed_step: None,
+            failed_job: None,
+        }
+    }
+
+    #[test]
+    fn check_status() {
+        let cases = [
+            ("completed", Status::Completed),
+            ("in_progress", Status::InProgress),
+            ("queued", Status::Queued),
+            ("requested", Status::Queued),
+            ("pending", Status::Queued),
+        ];
+
+        for (status, expected) in cases {
+            assert_eq!(super::check_status(status), expected, "{status}");
+        }
+    }
+
+    #[test]
+    fn conclusion_succeeded() {
+        let cases = [
+            ("success", true),
+            ("neutral", true),
+            ("skipped", true),
+            ("failure", false),
+            ("cancelled", false),
+            ("timed_out", false),
+            ("action_required", false),
+            ("stale", false),
+        ];
+
+        for (conclusion, expected) in cases {
+            assert_eq!(
+                super::conclusion_succeeded(conclusion),
+                expected,
+                "{conclusion}"
+            );
+        }
+    }
+
+    #[test]
+    fn update_workflow_status() {
+        // each step is applied to the same job in order
+        let cases = [
+            (update("check_run:1", Status::Queued, None), Status::Queued),
+            (
+                update("check_run:1", Status::InProgress, None),
+                Status::InProgress,
+            ),
+            // late queued deliveries do not send a running job back
+            (
+                update("check_run:1", Status::Queued, None),
+                Status::InProgress,
+            ),
+            (
+                update("check_run:1", Status::Completed, Some("failure")),
+                Status::Failed,
+            ),
+            // nor does anything without a conclusion reopen a finished one
+            (
+                update("check_run:1", Status::InProgress, None),
+                Status::Failed,
+            ),
+            (
+                update("check_run:1", Status::Completed, Some("success")),
+                Status::Completed,
+            ),
+            (
+                update("check_run:1", Status::Queued, None),
+                Status::Completed,
+            ),
+        ];
+
+        let mut workflow_status = indexmap::IndexMap::new();
+        for (step, (workflow_update, expected)) in cases.into_iter().enumerate() {
+            super::update_workflow_status(&mut workflow_status, &workflow_update);
+
+            assert_eq!(
+                workflow_status["check_run:1"].status, expected,
+                "step {step}"
+            );
+        }
+
+        assert_eq!(workflow_status.len(), 1);
+        assert_eq!(
+            workflow_status["check_run:1"].conclusion.as_deref(),
+            Some("success")
+        );
+    }
+
+    #[test]
+    fn update_workflow_status_keys() {
+        let mut workflow_status = indexmap::IndexMap::new();
+
+        let mut failed = update("42", Status::Completed, Some("failure"));
+        failed.failed_step = Some("Run tests".to_string());
+        super::update_workflow_status(&mut workflow_status, &failed);
+        super::update_workflow_status(
+            &mut workflow_status,
+            &update("check_run:42", Status::InProgress, None),
+        );
+        super::update_workflow_status(
+            &mut workflow_status,
+            &update("status:42", Status::Completed, Some("success")),
+        );
+
+        assert_eq!(
+            workflow_status
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+            ["42", "check_run:42", "status:42"]
+        );
+        assert_eq!(
+            workflow_status["42"].failed_step.as_deref(),
+            Some("Run tests")
+        );
+        assert_eq!(workflow_status["check_run:42"].status, Status::InProgress);
+        assert_eq!(workflow_status["status:42"].status, Status::Completed);
+    }
+}
@@ -4,7 +4,7 @@ use tokio::sync::RwLock;
 use utoipa::ToSchema;
 use utoipa_axum::router::OpenApiRouter;
 
-mod github;
+pub mod github;
 mod sponsors;
 
 #[derive(ToSchema, Serialize)]
@@ -29,6 +29,7 @@ pub struct InnerState {
 
     pub env: Arc<crate::env::Env>,
     pub database: Arc<crate::database::Database>,
+    pub renderers: crate::renderers::EventRendererList,
     pub bot: RwLock<Arc<serenity::http::Http>>,
 }
 
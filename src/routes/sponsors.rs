@@ -62,13 +62,13 @@ mod get {
         (status = SERVICE_UNAVAILABLE, body = ApiError),
     ))]
     pub async fn route(state: GetState) -> ApiResponseResult {
-        if state.env.github_token.is_none() {
+        if !crate::github::configured(&state.env) {
             return ApiResponse::error("sponsor data is not configured")
                 .with_status(StatusCode::SERVICE_UNAVAILABLE)
                 .ok();
         }
 
-        let ledger = match cached_ledger(&state.env).await {
+        let ledger = match cached_ledger(&state.env, &state.database).await {
             Ok(ledger) => ledger,
             Err(err) => {
                 tracing::error!("failed to fetch sponsors: {:?}", err);
@@ -79,11 +79,19 @@ impl EvaluatedSpell {
 
 #[derive(Debug, Clone)]
 pub struct EvaluatedSponsor {
+    /// `None` for private sponsors
     pub sponsor: Option<GitHubSponsor>,
+    /// Kept for private sponsors too so linked accounts can be matched, never shown publicly
+    pub github_id: Option<i64>,
+    pub login: String,
 
     pub active: bool,
     pub recurring_spells: usize,
     pub monthly_in_cents: i64,
+    /// Monthly amount of the most recent recurring sponsorship, even if it ended
+    pub last_monthly_in_cents: i64,
+    /// When the most recent recurring sponsorship ended, `None` while it is active
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
 
     pub one_time_in_cents: i64,
     pub recurring_in_cents: i64,
@@ -254,12 +262,18 @@ pub fn evaluate(ledger: &Ledger, now: chrono::DateTime<chrono::Utc>) -> Evaluati
             evaluation.one_time_sponsor_count += 1;
         }
 
+        let last_spell = state.spells.last();
+
         evaluation.sponsors.push(EvaluatedSponsor {
+            github_id: sponsor.database_id,
+            login: sponsor.login.clone(),
             sponsor: state.public.then_some(sponsor),
 
             active: recurring,
             recurring_spells: state.spells.len(),
             monthly_in_cents,
+            last_monthly_in_cents: last_spell.map_or(0, |spell| spell.monthly_in_cents()),
+            ended_at: last_spell.and_then(|spell| spell.end),
 
             one_time_in_cents,
             recurring_in_cents,
@@ -0,0 +1,112 @@
+use super::{EvaluatedSponsor, Evaluation, dollars, sponsors_login};
+use serenity::all::{
+    ButtonStyle, CreateActionRow, CreateButton, CreateComponent, CreateContainer,
+    CreateContainerComponent, CreateSection, CreateSectionAccessory, CreateSectionComponent,
+    CreateSeparator, CreateTextDisplay, CreateThumbnail, CreateUnfurledMediaItem,
+};
+
+pub const LEADERBOARD_PAGE_SIZE: usize = 10;
+
+/// Buttons carry the page they lead to, see `components::sponsors_leaderboard`
+pub const LEADERBOARD_PREVIOUS_PREFIX: &str = "sponsors_leaderboard_previous:";
+pub const LEADERBOARD_NEXT_PREFIX: &str = "sponsors_leaderboard_next:";
+
+fn leaderboard_entry(rank: usize, sponsor: &EvaluatedSponsor) -> String {
+    let mut entry = format!(
+        "**{rank}.** [**{login}**](https://github.com/{login}) `{}` in total",
+        dollars(sponsor.lifetime_in_cents),
+        login = sponsor.login
+    );
+
+    if sponsor.active {
+        entry.push_str(&format!(
+            ", `{}` monthly",
+            dollars(sponsor.monthly_in_cents)
+        ));
+    }
+
+    entry
+}
+
+/// Stats and one page of the leaderboard, private sponsors count towards the totals but are never listed
+pub fn leaderboard_components<'a>(
+    env: &crate::env::Env,
+    evaluation: &Evaluation,
+    page: usize,
+) -> Vec<CreateComponent<'a>> {
+    let public_sponsors = evaluation
+        .sponsors
+        .iter()
+        .filter(|sponsor| sponsor.sponsor.is_some())
+        .collect::<Vec<_>>();
+    let private_sponsors = evaluation.sponsors.len() - public_sponsors.len();
+
+    let pages = public_sponsors.len().div_ceil(LEADERBOARD_PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let mut leaderboard = public_sponsors
+        .iter()
+        .enumerate()
+        .skip(page * LEADERBOARD_PAGE_SIZE)
+        .take(LEADERBOARD_PAGE_SIZE)
+        .map(|(index, sponsor)| leaderboard_entry(index + 1, sponsor))
+        .collect::<Vec<_>>();
+    if leaderboard.is_empty() {
+        leaderboard.push("No public sponsors yet.".to_string());
+    }
+    if private_sponsors > 0 {
+        leaderboard.push(format!(
+            "-# {private_sponsors} private sponsor(s) are not listed"
+        ));
+    }
+
+    let login = sponsors_login(env);
+
+    vec![CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::Section(CreateSection::new(
+            vec![
+                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
+                    "## <:cash:1150889514236137605> Sponsors",
+                )),
+                CreateSectionComponent::TextDisplay(CreateTextDisplay::new(format!(
+                    "**Monthly**: `{}`\n**Lifetime**: `{}` (`{}` one-time, `{}` monthly)\n**Sponsors**: {} active, {} former, {} one-time",
+                    dollars(evaluation.monthly_recurring_in_cents),
+                    dollars(evaluation.lifetime_in_cents),
+                    dollars(evaluation.one_time_in_cents),
+                    dollars(evaluation.recurring_in_cents),
+                    evaluation.active_sponsor_count,
+                    evaluation.former_sponsor_count,
+                    evaluation.one_time_sponsor_count
+                ))),
+            ],
+            CreateSectionAccessory::Thumbnail(CreateThumbnail::new(CreateUnfurledMediaItem::new(
+                format!("https://github.com/{login}.png"),
+            ))),
+        )),
+        CreateContainerComponent::Separator(CreateSeparator::new()),
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(leaderboard.join("\n"))),
+        CreateContainerComponent::Separator(CreateSeparator::new()),
+        CreateContainerComponent::ActionRow(CreateActionRow::Buttons(
+            vec![
+                CreateButton::new(format!(
+                    "{LEADERBOARD_PREVIOUS_PREFIX}{}",
+                    page.saturating_sub(1)
+                ))
+                .label("Previous")
+                .style(ButtonStyle::Secondary)
+                .disabled(page == 0),
+                CreateButton::new(format!("{LEADERBOARD_NEXT_PREFIX}{}", page + 1))
+                    .label("Next")
+                    .style(ButtonStyle::Secondary)
+                    .disabled(page + 1 >= pages),
+                CreateButton::new_link(format!("https://github.com/sponsors/{login}"))
+                    .label("Sponsor"),
+            ]
+            .into(),
+        )),
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
+            "-# Page {} of {pages}",
+            page + 1
+        ))),
+    ]))]
+}
@@ -3,6 +3,10 @@ use std::sync::{Arc, LazyLock};
 
 mod evaluate;
 pub use evaluate::*;
+mod leaderboard;
+pub use leaderboard::*;
+mod roles;
+pub use roles::*;
 mod task;
 pub use task::*;
 
@@ -112,13 +116,6 @@ pub struct Ledger {
     pub monthly_estimated_income_in_cents: Option<i64>,
 }
 
-static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
-    reqwest::Client::builder()
-        .user_agent(format!("Calagopus Bot ({})", crate::VERSION))
-        .build()
-        .expect("failed to build sponsors http client")
-});
-
 static LEDGER_CACHE: LazyLock<moka::future::Cache<(), Arc<Ledger>>> = LazyLock::new(|| {
     moka::future::Cache::builder()
         .time_to_live(std::time::Duration::from_secs(300))
@@ -131,10 +128,22 @@ pub fn sponsors_login(env: &crate::env::Env) -> &str {
     env.github_sponsors_login.as_deref().unwrap_or("calagopus")
 }
 
-fn graphql_query(env: &crate::env::Env, after: Option<&str>) -> String {
+fn dollars(cents: i64) -> String {
+    format!("${:.2}", cents as f64 / 100.0)
+}
+
+fn graphql_query(
+    env: &crate::env::Env,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    after: Option<&str>,
+) -> String {
     let after = after
         .map(|c| format!(r#", after: "{c}""#))
         .unwrap_or_default();
+    // github ignores the period once `since` is given
+    let since = since
+        .map(|s| format!(r#", since: "{}""#, s.to_rfc3339()))
+        .unwrap_or_default();
 
     let login = sponsors_login(env);
 
@@ -143,7 +152,7 @@ fn graphql_query(env: &crate::env::Env, after: Option<&str>) -> String {
         query {{
             organization(login: "{login}") {{
                 monthlyEstimatedSponsorsIncomeInCents
-                sponsorsActivities(first: 100, period: ALL, includePrivate: true, orderBy: {{field: TIMESTAMP, direction: ASC}}{after}) {{
+                sponsorsActivities(first: 100, period: ALL, includePrivate: true, orderBy: {{field: TIMESTAMP, direction: ASC}}{since}{after}) {{
                     nodes {{
                         id
                         action
@@ -185,10 +194,15 @@ fn graphql_query(env: &crate::env::Env, after: Option<&str>) -> String {
     )
 }
 
-/// Old to new
-pub async fn collect_sponsors(env: &crate::env::Env) -> Result<Ledger, anyhow::Error> {
-    let Some(github_token) = &env.github_token else {
-        return Err(anyhow::anyhow!("GITHUB_TOKEN is not configured"));
+/// Activities at or after `since`, the whole history if `None`. Old to new
+pub async fn collect_sponsors(
+    env: &crate::env::Env,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<Ledger, anyhow::Error> {
+    let Some(client) = crate::github::client(env)? else {
+        return Err(anyhow::anyhow!(
+            "neither GITHUB_TOKEN nor a GitHub App is configured"
+        ));
     };
 
     let mut activities = Vec::new();
@@ -196,15 +210,9 @@ pub async fn collect_sponsors(env: &crate::env::Env) -> Result<Ledger, anyhow::E
     let mut after = None;
 
     loop {
-        let res = CLIENT
-            .post("https://api.github.com/graphql")
-            .bearer_auth(github_token)
-            .json(&serde_json::json!({ "query": graphql_query(env, after.as_deref()) }))
-            .send()
-            .await?
-            .error_for_status()?;
-
-        let response: GitHubSponsorsResponse = res.json().await?;
+        let response: GitHubSponsorsResponse = client
+            .graphql(&serde_json::json!({ "query": graphql_query(env, since, after.as_deref()) }))
+            .await?;
 
         if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
             return Err(anyhow::anyhow!("github graphql errors: {:?}", errors));
@@ -239,16 +247,72 @@ pub async fn collect_sponsors(env: &crate::env::Env) -> Result<Ledger, anyhow::E
     })
 }
 
-pub async fn refresh_ledger(env: &crate::env::Env) -> Result<Arc<Ledger>, anyhow::Error> {
-    let ledger = Arc::new(collect_sponsors(env).await?);
+/// Older activities can still change, e.g. a sponsor going private or deleting their account,
+/// so the whole history is fetched again this often
+const FULL_SYNC_INTERVAL: chrono::Duration = chrono::Duration::hours(24);
+static LAST_FULL_SYNC: LazyLock<tokio::sync::Mutex<Option<chrono::DateTime<chrono::Utc>>>> =
+    LazyLock::new(|| tokio::sync::Mutex::new(None));
+
+/// Stores activities newer than the last stored one and returns the whole persisted ledger.
+/// The newest timestamp is fetched again, activities sharing it may not all have been stored.
+/// Once per [`FULL_SYNC_INTERVAL`], and on the first sync after a restart, the stored ledger
+/// is replaced with the whole history instead.
+pub async fn sync_ledger(
+    env: &crate::env::Env,
+    database: &crate::database::Database,
+) -> Result<Ledger, anyhow::Error> {
+    let full_sync = LAST_FULL_SYNC
+        .lock()
+        .await
+        .is_none_or(|last_full_sync| last_full_sync + FULL_SYNC_INTERVAL <= chrono::Utc::now());
+
+    let since = if full_sync {
+        None
+    } else {
+        crate::models::latest_sponsors_activity_timestamp(database.read()).await?
+    };
+    let fetched = collect_sponsors(env, since).await?;
+
+    if full_sync {
+        tracing::debug!(
+            "replacing stored sponsors activities with {} fetched ones",
+            fetched.activities.len()
+        );
+
+        crate::models::replace_sponsors_activities(database.write(), &fetched.activities).await?;
+        *LAST_FULL_SYNC.lock().await = Some(chrono::Utc::now());
+    } else if !fetched.activities.is_empty() {
+        tracing::debug!(
+            "storing {} sponsors activities since {:?}",
+            fetched.activities.len(),
+            since
+        );
+
+        crate::models::upsert_sponsors_activities(database.write(), &fetched.activities).await?;
+    }
+
+    Ok(Ledger {
+        activities: crate::models::all_sponsors_activities(database.read()).await?,
+        monthly_estimated_income_in_cents: fetched.monthly_estimated_income_in_cents,
+    })
+}
+
+pub async fn refresh_ledger(
+    env: &crate::env::Env,
+    database: &crate::database::Database,
+) -> Result<Arc<Ledger>, anyhow::Error> {
+    let ledger = Arc::new(sync_ledger(env, database).await?);
     LEDGER_CACHE.insert((), ledger.clone()).await;
 
     Ok(ledger)
 }
 
-pub async fn cached_ledger(env: &crate::env::Env) -> Result<Arc<Ledger>, anyhow::Error> {
+pub async fn cached_ledger(
+    env: &crate::env::Env,
+    database: &crate::database::Database,
+) -> Result<Arc<Ledger>, anyhow::Error> {
     LEDGER_CACHE
-        .try_get_with((), async { collect_sponsors(env).await.map(Arc::new) })
+        .try_get_with((), async { sync_ledger(env, database).await.map(Arc::new) })
         .await
         .map_err(|err| anyhow::anyhow!("{err}"))
 }
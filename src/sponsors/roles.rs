@@ -0,0 +1,119 @@
+use super::{EvaluatedSponsor, Evaluation, cached_ledger, evaluate};
+use indexmap::IndexMap;
+use serenity::all::{GuildId, RoleId, UserId};
+
+/// The evaluated sponsor behind a verified link, matched by login for sponsors without a database id
+pub fn linked_sponsor<'a>(
+    evaluation: &'a Evaluation,
+    link: &crate::models::SponsorLink,
+) -> Option<&'a EvaluatedSponsor> {
+    evaluation
+        .sponsors
+        .iter()
+        .find(|sponsor| match sponsor.github_id {
+            Some(github_id) => link.github_id == Some(github_id),
+            None => sponsor.login.eq_ignore_ascii_case(&link.github_login),
+        })
+}
+
+/// Adds and removes configured roles of one member, roles that are not configured are never touched.
+/// A role with several rules is kept as long as any of them qualifies.
+/// Users that are not in the guild are skipped, any other lookup failure is returned.
+pub async fn apply_sponsor_roles(
+    state: &crate::routes::State,
+    roles: &[crate::models::SponsorRole],
+    guild_id: u64,
+    discord_id: u64,
+    sponsor: Option<&EvaluatedSponsor>,
+) -> Result<(), anyhow::Error> {
+    let http = state.bot.read().await.clone();
+    let now = chrono::Utc::now();
+
+    let member = match http
+        .get_member(GuildId::new(guild_id), UserId::new(discord_id))
+        .await
+    {
+        Ok(member) => member,
+        Err(serenity::Error::Http(serenity::all::HttpError::UnsuccessfulRequest(response)))
+            if response.error.code == serenity::http::JsonErrorCode::UnknownMember =>
+        {
+            tracing::debug!("skipping sponsor roles of {discord_id}, not a member");
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut qualified: IndexMap<u64, bool> = IndexMap::new();
+    for role in roles {
+        let qualifies = sponsor.is_some_and(|sponsor| {
+            role.qualifies(sponsor, now, state.env.github_sponsors_role_grace)
+        });
+
+        *qualified.entry(role.role_id).or_default() |= qualifies;
+    }
+
+    for (role_id, qualifies) in qualified {
+        let has_role = member.roles.iter().any(|r| r.get() == role_id);
+
+        if qualifies && !has_role {
+            http.add_member_role(member.guild_id, member.user.id, RoleId::new(role_id), None)
+                .await?;
+        } else if !qualifies && has_role {
+            http.remove_member_role(member.guild_id, member.user.id, RoleId::new(role_id), None)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn sync_sponsor_roles(state: &crate::routes::State) -> Result<(), anyhow::Error> {
+    let Some(guild_id) = state.env.github_sponsors_guild_id else {
+        return Ok(());
+    };
+
+    let roles = crate::models::all_sponsor_roles(state.database.read()).await?;
+    if roles.is_empty() {
+        return Ok(());
+    }
+
+    let ledger = cached_ledger(&state.env, &state.database).await?;
+    let evaluation = evaluate(&ledger, chrono::Utc::now());
+
+    for link in crate::models::verified_sponsor_links(state.database.read()).await? {
+        if let Err(err) = apply_sponsor_roles(
+            state,
+            &roles,
+            guild_id,
+            link.discord_id as u64,
+            linked_sponsor(&evaluation, &link),
+        )
+        .await
+        {
+            tracing::error!(
+                "failed to sync sponsor roles of {}: {:?}",
+                link.discord_id,
+                err
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn spawn_sponsor_roles_task(state: crate::routes::State) {
+    tokio::spawn(async move {
+        if state.env.github_sponsors_guild_id.is_none() || !crate::github::configured(&state.env) {
+            return;
+        }
+
+        loop {
+            if let Err(err) = sync_sponsor_roles(&state).await {
+                tracing::error!("failed to sync sponsor roles: {:?}", err);
+                sentry_anyhow::capture_anyhow(&err);
+            }
+
+            tokio::time::sleep(std::time::Duration::from_mins(15)).await;
+        }
+    });
+}
@@ -1,19 +1,24 @@
-use super::{EvaluatedSpell, SponsorsActivityAction, evaluate, refresh_ledger, sponsors_login};
+use super::{
+    EvaluatedSpell, SponsorsActivityAction, dollars, evaluate, refresh_ledger, sponsors_login,
+};
 use serenity::all::{
     Component, ContainerComponent, CreateComponent, CreateContainer, CreateContainerComponent,
     CreateMessage, CreateSection, CreateSectionAccessory, CreateSectionComponent,
     CreateTextDisplay, CreateThumbnail, CreateUnfurledMediaItem, EditMessage, GenericChannelId,
     MessageFlags, MessagePagination, Section, SectionComponent, nonmax::NonMaxU8,
 };
+use std::sync::LazyLock;
+
+static SPONSOR_UPDATES_NOTIFY: LazyLock<tokio::sync::Notify> =
+    LazyLock::new(tokio::sync::Notify::new);
+
+/// Give the graphql api a moment to catch up with the webhook that triggered an update
+const TRIGGER_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
 
 const HEADING_ONE_TIME: &str = "## <:cash:1150889514236137605> Sponsorship received";
 const HEADING_MONTHLY: &str = "## <:cash:1150889514236137605> Monthly sponsorship";
 const HEADING_MONTHLY_ENDED: &str = "## <:cash:1150889514236137605> Monthly sponsorship ended";
 
-fn dollars(cents: i64) -> String {
-    format!("${:.2}", cents as f64 / 100.0)
-}
-
 fn sponsor_link(spell: &EvaluatedSpell) -> String {
     match &spell.sponsor {
         Some(sponsor) => format!(
@@ -256,7 +261,7 @@ async fn run(
     channel_id: u64,
     backfilled: &mut bool,
 ) -> Result<(), anyhow::Error> {
-    let ledger = refresh_ledger(&state.env).await?;
+    let ledger = refresh_ledger(&state.env, &state.database).await?;
     let evaluation = evaluate(&ledger, chrono::Utc::now());
 
     let announceable = ledger
@@ -458,14 +463,22 @@ async fn run(
     Ok(())
 }
 
+/// Runs the sponsors task right away instead of on its next poll, e.g. for sponsorship webhooks
+#[inline]
+pub fn trigger_sponsor_update() {
+    SPONSOR_UPDATES_NOTIFY.notify_one();
+}
+
 pub fn spawn_sponsor_updates_task(state: crate::routes::State) {
     tokio::spawn(async move {
         let Some(channel_id) = state.env.github_sponsors_channel_id else {
             return;
         };
 
-        if state.env.github_token.is_none() {
-            tracing::warn!("GITHUB_TOKEN is not set, sponsorship updates are disabled");
+        if !crate::github::configured(&state.env) {
+            tracing::warn!(
+                "neither GITHUB_TOKEN nor a GitHub App is configured, sponsorship updates are disabled"
+            );
             return;
         }
 
@@ -477,7 +490,14 @@ pub fn spawn_sponsor_updates_task(state: crate::routes::State) {
                 sentry_anyhow::capture_anyhow(&err);
             }
 
-            tokio::time::sleep(std::time::Duration::from_mins(5)).await;
+            // polling stays as reconciliation for webhooks that were missed
+            tokio::select! {
+                _ = SPONSOR_UPDATES_NOTIFY.notified() => {
+                    tracing::debug!("sponsorship webhook received, updating sponsors");
+                    tokio::time::sleep(TRIGGER_DELAY).await;
+                },
+                _ = tokio::time::sleep(std::time::Duration::from_mins(5)) => {},
+            }
         }
     });
 }
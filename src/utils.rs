@@ -21,3 +21,178 @@ pub fn extract_ip(headers: &HeaderMap) -> Option<IpAddr> {
 
     ip.parse().ok()
 }
+
+/// Case-insensitive glob matching, supporting `*` and `?` wildcards
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            backtrack = Some((star, matched + 1));
+            p = star + 1;
+            t = matched + 1;
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Truncates `text` to at most `max_chars` characters, marking the cut with an ellipsis
+pub fn truncate_text(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    } else if max_chars == 0 {
+        return String::new();
+    }
+
+    let mut truncated = text
+        .chars()
+        .take(max_chars.saturating_sub(1))
+        .collect::<String>();
+    truncated.push('…');
+
+    truncated
+}
+
+/// Breaks up `@everyone`, `@here` and `<@id>` style mentions with a zero width space
+pub fn neutralize_mentions(text: &str) -> String {
+    text.replace('@', "@\u{200b}")
+}
+
+/// Formats a duration like `1h 2m 3s`, leaving out leading zero units
+pub fn format_duration(duration: chrono::Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}h {minutes}m {seconds}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn glob_matches() {
+        let cases = [
+            ("", "", true),
+            ("", "main", false),
+            ("*", "", true),
+            ("*", "release/1.0/hotfix", true),
+            ("main", "main", true),
+            ("main", "MAIN", true),
+            ("main", "mainline", false),
+            ("main", "mai", false),
+            ("release/*", "release/1.0", true),
+            ("release/*", "release", false),
+            // `*` also matches slashes, so `**` behaves the same
+            ("release/*", "release/1.0/hotfix", true),
+            ("release/**", "release/1.0/hotfix", true),
+            ("**", "a/b/c", true),
+            ("calagopus/*", "calagopus/panel", true),
+            ("calagopus/*", "other/panel", false),
+            ("*/panel", "calagopus/panel", true),
+            ("v?.0", "v1.0", true),
+            ("v?.0", "v10.0", false),
+            ("?", "", false),
+            ("?", "é", true),
+            ("*-rc*", "v1.0-rc2", true),
+            ("a*b*c", "aXbYc", true),
+            ("a*b*c", "aXbY", false),
+            ("a*a*a", "aaaa", true),
+        ];
+
+        for (pattern, text, expected) in cases {
+            assert_eq!(
+                super::glob_matches(pattern, text),
+                expected,
+                "{pattern:?} matching {text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn truncate_text() {
+        let cases = [
+            ("", 0, ""),
+            ("", 5, ""),
+            ("hello", 0, ""),
+            ("hello", 1, "…"),
+            ("hello", 4, "hel…"),
+            ("hello", 5, "hello"),
+            ("hello", 6, "hello"),
+            // limits count characters, multi-byte ones are never split
+            ("héllo", 3, "hé…"),
+            ("héllo", 5, "héllo"),
+            ("日本語のテキスト", 4, "日本語…"),
+            ("🦀🦀🦀", 2, "🦀…"),
+            ("🦀🦀🦀", 3, "🦀🦀🦀"),
+        ];
+
+        for (text, max_chars, expected) in cases {
+            let truncated = super::truncate_text(text, max_chars);
+
+            assert_eq!(truncated, expected, "{text:?} to {max_chars}");
+            assert!(truncated.chars().count() <= max_chars || text.is_empty());
+        }
+    }
+
+    #[test]
+    fn neutralize_mentions() {
+        let cases = [
+            ("no mentions", "no mentions"),
+            ("@everyone", "@\u{200b}everyone"),
+            ("@here", "@\u{200b}here"),
+            ("@EVERYONE look", "@\u{200b}EVERYONE look"),
+            ("@@everyone", "@\u{200b}@\u{200b}everyone"),
+            ("hi <@123456789>", "hi <@\u{200b}123456789>"),
+            ("<@!123456789>", "<@\u{200b}!123456789>"),
+            ("<@&987654321>", "<@\u{200b}&987654321>"),
+            ("user@example.com", "user@\u{200b}example.com"),
+        ];
+
+        for (text, expected) in cases {
+            let neutralized = super::neutralize_mentions(text);
+
+            assert_eq!(neutralized, expected, "{text:?}");
+            assert!(!neutralized.contains("@everyone") && !neutralized.contains("@here"));
+        }
+    }
+
+    #[test]
+    fn format_duration() {
+        let cases = [
+            (chrono::Duration::zero(), "0s"),
+            (chrono::Duration::milliseconds(999), "0s"),
+            (chrono::Duration::seconds(-30), "0s"),
+            (chrono::Duration::seconds(59), "59s"),
+            (chrono::Duration::seconds(60), "1m 0s"),
+            (chrono::Duration::seconds(3599), "59m 59s"),
+            (chrono::Duration::seconds(3600), "1h 0m 0s"),
+            (chrono::Duration::seconds(3723), "1h 2m 3s"),
+            // days are not split out
+            (chrono::Duration::days(3), "72h 0m 0s"),
+            (chrono::Duration::MAX, "2562047788015h 12m 55s"),
+            (chrono::Duration::MIN, "0s"),
+        ];
+
+        for (duration, expected) in cases {
+            assert_eq!(super::format_duration(duration), expected, "{duration:?}");
+        }
+    }
+}